@@ -0,0 +1,80 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use data_encoding::BASE32HEX_NOPAD;
+use typed_oid::{
+    encoding::{Base32Hex, Crockford},
+    Oid, OidEncoding, OidPrefix, OidStr,
+};
+use uuid::Uuid;
+
+struct Tst;
+impl OidPrefix for Tst {}
+
+struct Evt;
+impl OidPrefix for Evt {
+    fn encoding() -> &'static dyn OidEncoding { &Crockford }
+}
+
+const VALUE: &str = "0OUS781P4LU7V000PA2A2BN1GC";
+const CROCKFORD_VALUE: &str = "067Q1T0E95FHZR006AGJGJXRC3";
+
+fn decode(c: &mut Criterion) {
+    let mut g = c.benchmark_group("decode");
+    // The decoding path used prior to the specialized decoder
+    g.bench_function("data_encoding_vec_from_slice", |b| {
+        b.iter(|| {
+            Uuid::from_slice(&BASE32HEX_NOPAD.decode(black_box(VALUE).as_bytes()).unwrap()).unwrap()
+        })
+    });
+    g.bench_function("data_encoding_decode_mut", |b| {
+        b.iter(|| {
+            let mut bytes = [0; 16];
+            BASE32HEX_NOPAD
+                .decode_mut(black_box(VALUE).as_bytes(), &mut bytes)
+                .unwrap();
+            Uuid::from_bytes(bytes)
+        })
+    });
+    // Statically dispatched decoding, to compare against `OidPrefix::encoding`
+    g.bench_function("base32hex_decode", |b| {
+        b.iter(|| Base32Hex.decode(black_box(VALUE)).unwrap())
+    });
+    g.bench_function("oid_try_with_uuid_base32", |b| {
+        b.iter(|| Oid::<Tst>::try_with_uuid_base32(black_box(VALUE)).unwrap())
+    });
+    g.bench_function("oid_from_str", |b| {
+        b.iter(|| {
+            black_box("Tst-0OUS781P4LU7V000PA2A2BN1GC")
+                .parse::<Oid<Tst>>()
+                .unwrap()
+        })
+    });
+    g.bench_function("oidstr_from_str", |b| {
+        b.iter(|| {
+            black_box("TST-0OQPKOAADLRUJ000J7U2UGNS2G")
+                .parse::<OidStr>()
+                .unwrap()
+        })
+    });
+    g.finish();
+
+    let mut g = c.benchmark_group("decode_crockford");
+    g.bench_function("crockford_decode", |b| {
+        b.iter(|| Crockford.decode(black_box(CROCKFORD_VALUE)).unwrap())
+    });
+    // A virtual call which the optimizer can't see through (the worst case)
+    g.bench_function("crockford_dyn_decode", |b| {
+        let encoding: &dyn OidEncoding = black_box(&Crockford);
+        b.iter(|| encoding.decode(black_box(CROCKFORD_VALUE)).unwrap())
+    });
+    g.bench_function("oid_from_str", |b| {
+        b.iter(|| {
+            black_box("Evt-067Q1T0E95FHZR006AGJGJXRC3")
+                .parse::<Oid<Evt>>()
+                .unwrap()
+        })
+    });
+    g.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+use std::fmt::Write;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use data_encoding::BASE32HEX_NOPAD;
+use typed_oid::{
+    encoding::{Base32Hex, Crockford},
+    Case, Oid, OidEncoding, OidPrefix, OidStr, MAX_VALUE_LEN,
+};
+
+struct Tst;
+impl OidPrefix for Tst {}
+
+struct Evt;
+impl OidPrefix for Evt {
+    fn encoding() -> &'static dyn OidEncoding { &Crockford }
+}
+
+fn encode(c: &mut Criterion) {
+    let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+    let oidstr: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+
+    let mut g = c.benchmark_group("encode");
+    g.bench_function("data_encoding_string", |b| {
+        b.iter(|| BASE32HEX_NOPAD.encode(black_box(&oid).uuid().as_bytes()))
+    });
+    g.bench_function("oid_value", |b| b.iter(|| black_box(&oid).value()));
+    g.bench_function("oid_encode_value", |b| {
+        let mut buf = [0; MAX_VALUE_LEN];
+        b.iter(|| black_box(black_box(&oid).encode_value(&mut buf).len()))
+    });
+    // Statically dispatched encoding, to compare against `OidPrefix::encoding`
+    g.bench_function("base32hex_encode", |b| {
+        let mut buf = [0; MAX_VALUE_LEN];
+        b.iter(|| {
+            let value = Base32Hex.encode(black_box(oid.uuid()), Case::Upper, &mut buf);
+            black_box(value.len())
+        })
+    });
+    g.bench_function("oid_to_buf", |b| b.iter(|| black_box(&oid).to_buf()));
+    g.bench_function("oid_to_string", |b| b.iter(|| black_box(&oid).to_string()));
+    g.bench_function("oid_display", |b| {
+        let mut out = String::with_capacity(64);
+        b.iter(|| {
+            out.clear();
+            write!(out, "{}", black_box(&oid)).unwrap();
+        })
+    });
+    g.bench_function("oidstr_to_buf", |b| b.iter(|| black_box(&oidstr).to_buf()));
+    g.bench_function("oidstr_display", |b| {
+        let mut out = String::with_capacity(64);
+        b.iter(|| {
+            out.clear();
+            write!(out, "{}", black_box(&oidstr)).unwrap();
+        })
+    });
+    g.finish();
+
+    let evt: Oid<Evt> = Oid::with_uuid(*oid.uuid());
+    let mut g = c.benchmark_group("encode_crockford");
+    g.bench_function("crockford_encode", |b| {
+        let mut buf = [0; MAX_VALUE_LEN];
+        b.iter(|| {
+            let value = Crockford.encode(black_box(evt.uuid()), Case::Upper, &mut buf);
+            black_box(value.len())
+        })
+    });
+    // A virtual call which the optimizer can't see through (the worst case)
+    g.bench_function("crockford_dyn_encode", |b| {
+        let encoding: &dyn OidEncoding = black_box(&Crockford);
+        let mut buf = [0; MAX_VALUE_LEN];
+        b.iter(|| {
+            let value = encoding.encode(black_box(evt.uuid()), Case::Upper, &mut buf);
+            black_box(value.len())
+        })
+    });
+    g.bench_function("oid_encode_value", |b| {
+        let mut buf = [0; MAX_VALUE_LEN];
+        b.iter(|| black_box(black_box(&evt).encode_value(&mut buf).len()))
+    });
+    g.bench_function("oid_to_buf", |b| b.iter(|| black_box(&evt).to_buf()));
+    g.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
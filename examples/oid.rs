@@ -1,4 +1,6 @@
-use typed_oid::{error::*, OidPrefix};
+use typed_oid::error::*;
+#[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+use typed_oid::OidPrefix;
 
 #[cfg(not(feature = "uuid_v4"))]
 fn oid_v4() -> Result<()> {
@@ -13,7 +15,9 @@ fn oid_v7() -> Result<()> {
 
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug)]
+#[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
 struct EXA;
+#[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
 impl OidPrefix for EXA {}
 
 fn main() -> Result<()> {
@@ -50,7 +54,13 @@ fn oid_v4() -> Result<()> {
     // we get an error even if the UUID is valid
     let res = "FAIL-4GKFGPRVND4QT3PDR90PDKF66O".parse::<Oid<EXA>>();
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 0 });
+    assert_eq!(
+        res.unwrap_err(),
+        Error::UnexpectedPrefix {
+            expected: "EXA",
+            found: "FAIL".parse()?
+        }
+    );
 
     Ok(())
 }
@@ -82,7 +92,13 @@ fn oid_v7() -> Result<()> {
     // we get an error even if the UUID is valid
     let res = "FAIL-066F28J3RDQ33EB4QM8LVP0TGK".parse::<Oid<EXA>>();
     assert!(res.is_err());
-    assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 0 });
+    assert_eq!(
+        res.unwrap_err(),
+        Error::UnexpectedPrefix {
+            expected: "EXA",
+            found: "FAIL".parse()?
+        }
+    );
 
     Ok(())
 }
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use typed_oid::{Oid, OidPrefix, OidStr, Prefix};
+
+struct Tst;
+impl OidPrefix for Tst {}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Prefix::from_slice(data);
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = s.parse::<Prefix>();
+        let _ = s.parse::<OidStr>();
+        let _ = s.parse::<Oid<Tst>>();
+    }
+});
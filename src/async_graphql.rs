@@ -0,0 +1,329 @@
+//! [`async-graphql`](::async_graphql) support for TOIDs
+//!
+//! An [`Oid`] is a custom scalar named after its prefix (e.g. `Oid_Usr`) so
+//! the type information isn't lost in the schema, and input values with any
+//! other prefix are rejected. An [`OidStr`] is a scalar named `OidStr` which
+//! accepts any valid TOID.
+//!
+//! For [Relay](https://relay.dev/graphql/objectidentification.htm) style
+//! global object identification a TOID can instead be exposed as the standard
+//! `ID` scalar with [`GlobalId`], whose prefix is enough for a `node(id:)`
+//! resolver to decide which type to load.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+//! # use typed_oid::{async_graphql::GlobalId, Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn user(&self, id: Oid<Usr>) -> Oid<Usr> { id }
+//!
+//!     async fn node(&self, id: GlobalId) -> Option<String> {
+//!         match id.prefix().as_str() {
+//!             "Usr" => Some(format!("user {}", id.to_oid::<Usr>().ok()?.uuid())),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+//! assert!(schema.sdl().contains("scalar Oid_Usr"));
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let res = schema
+//!     .execute(r#"{ node(id: "Usr-0OUS781P4LU7V000PA2A2BN1GC") }"#)
+//!     .await;
+//! assert_eq!(
+//!     res.data.to_string(),
+//!     r#"{node: "user 063dc3a0-3925-7c7f-8000-ca84a12ee183"}"#
+//! );
+//! # });
+//! ```
+
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use ::async_graphql::{
+    parser::types::Field, registry::Registry, ContextSelectionSet, InputType, InputValueError,
+    InputValueResult, OutputType, Positioned, Scalar, ScalarType, ServerResult, TypeName, Value,
+    ID,
+};
+
+use crate::{
+    error::{Error, Result},
+    oid::{prefix_matches, unexpected_prefix},
+    Oid, OidPrefix, OidStr, Prefix,
+};
+
+impl<P: OidPrefix> TypeName for Oid<P> {
+    fn type_name() -> Cow<'static, str> { format!("Oid_{}", P::prefix()).into() }
+}
+
+/// A typed object ID
+#[Scalar(name_type)]
+impl<P: OidPrefix + 'static> ScalarType for Oid<P> {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => s.parse().map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::String(s) if s.parse::<Self>().is_ok())
+    }
+
+    fn to_value(&self) -> Value { Value::String(self.to_string()) }
+}
+
+/// A typed object ID with any prefix
+#[Scalar(name = "OidStr")]
+impl ScalarType for OidStr {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => s.parse().map_err(InputValueError::custom),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::String(s) if s.parse::<Self>().is_ok())
+    }
+
+    fn to_value(&self) -> Value { Value::String(self.to_string()) }
+}
+
+/// A TOID used as a Relay global object ID
+///
+/// In the schema this is the standard `ID` scalar. Input values must start with
+/// a valid prefix and a separator, the prefix identifying the type of the
+/// object. As the separator and value encoding depend on that type (see
+/// [`OidPrefix`]) the value is only checked once converted into an [`Oid`] of
+/// the type with [`GlobalId::to_oid`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobalId {
+    id: String,
+    prefix: Prefix,
+}
+
+impl GlobalId {
+    /// The prefix identifying the type of the object
+    pub fn prefix(&self) -> &Prefix { &self.prefix }
+
+    /// Convert into an [`Oid`] of `P`, parsing the ID with the format of `P`
+    /// and failing if the prefix doesn't match
+    pub fn to_oid<P: OidPrefix>(&self) -> Result<Oid<P>> {
+        if !prefix_matches::<P>(self.prefix.as_str()) {
+            return Err(unexpected_prefix::<P>(self.prefix.as_str()));
+        }
+        self.id.parse()
+    }
+
+    /// Get the ID as a string
+    pub fn as_str(&self) -> &str { &self.id }
+}
+
+impl FromStr for GlobalId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // The separator is the first character which can't be part of a prefix
+        let (pfx, sep) = match s.char_indices().find(|(_, c)| !c.is_ascii_alphanumeric()) {
+            Some((i, sep)) => (&s[..i], sep),
+            None => return Err(Error::MissingSeparator),
+        };
+        if pfx.is_empty() {
+            return Err(Error::MissingPrefix);
+        }
+        if s.len() == pfx.len() + sep.len_utf8() {
+            return Err(Error::MissingValue);
+        }
+        Ok(Self {
+            prefix: pfx.parse()?,
+            id: s.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for GlobalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.id) }
+}
+
+impl From<OidStr> for GlobalId {
+    fn from(oid: OidStr) -> Self {
+        Self {
+            id: oid.to_string(),
+            prefix: oid.prefix().clone(),
+        }
+    }
+}
+
+impl<P: OidPrefix> From<Oid<P>> for GlobalId {
+    fn from(oid: Oid<P>) -> Self {
+        Self {
+            id: oid.to_string(),
+            // The prefix of `P` is already trusted when rendering the TOID
+            prefix: Prefix::from_slice_unchecked(oid.prefix_str().as_bytes()),
+        }
+    }
+}
+
+/// Parses the ID as an [`OidStr`] in the canonical format
+impl TryFrom<GlobalId> for OidStr {
+    type Error = Error;
+
+    fn try_from(id: GlobalId) -> Result<Self> { id.id.parse() }
+}
+
+impl InputType for GlobalId {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> { <ID as InputType>::type_name() }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        <ID as InputType>::create_type_info(registry)
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        match value.unwrap_or_default() {
+            Value::String(s) => s.parse().map_err(InputValueError::custom),
+            value => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value { Value::String(self.id.clone()) }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> { Some(self) }
+}
+
+impl OutputType for GlobalId {
+    fn type_name() -> Cow<'static, str> { <ID as OutputType>::type_name() }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        <ID as OutputType>::create_type_info(registry)
+    }
+
+    async fn resolve(
+        &self,
+        _ctx: &ContextSelectionSet<'_>,
+        _field: &Positioned<Field>,
+    ) -> ServerResult<Value> {
+        Ok(Value::String(self.id.clone()))
+    }
+}
+
+#[cfg(test)]
+mod async_graphql_tests {
+    use ::async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+    use super::*;
+    use crate::{encoding::Crockford, Case, OidEncoding};
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    struct Frm;
+    impl OidPrefix for Frm {}
+
+    struct Evt;
+    impl OidPrefix for Evt {
+        fn encoding() -> &'static dyn OidEncoding { &Crockford }
+
+        fn separator() -> char { '_' }
+
+        fn value_case() -> Case { Case::Lower }
+    }
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn tst(&self, id: Oid<Tst>) -> Oid<Tst> { id }
+
+        async fn any(&self, id: OidStr) -> OidStr { id }
+
+        async fn node(&self, id: GlobalId) -> GlobalId { id }
+    }
+
+    async fn execute(query: &str) -> ::async_graphql::Response {
+        Schema::new(Query, EmptyMutation, EmptySubscription)
+            .execute(query)
+            .await
+    }
+
+    #[test]
+    fn sdl() {
+        let sdl = Schema::new(Query, EmptyMutation, EmptySubscription).sdl();
+        assert!(sdl.contains("scalar Oid_Tst"));
+        assert!(sdl.contains("scalar OidStr"));
+        assert!(sdl.contains("node(id: ID!): ID!"));
+    }
+
+    #[tokio::test]
+    async fn oid_scalar() {
+        let res = execute(&format!(r#"{{ tst(id: "{TOID}") any(id: "{TOID}") }}"#)).await;
+        assert!(res.errors.is_empty(), "{:?}", res.errors);
+        assert_eq!(
+            res.data.to_string(),
+            format!(r#"{{tst: "{TOID}", any: "{TOID}"}}"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn oid_wrong_prefix() {
+        let res = execute(r#"{ tst(id: "Frm-0OUS781P4LU7V000PA2A2BN1GC") }"#).await;
+        assert_eq!(res.errors.len(), 1);
+        let res = execute(r#"{ any(id: "nope") }"#).await;
+        assert_eq!(res.errors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn global_id() {
+        let res = execute(&format!(r#"{{ node(id: "{TOID}") }}"#)).await;
+        assert!(res.errors.is_empty(), "{:?}", res.errors);
+        assert_eq!(res.data.to_string(), format!(r#"{{node: "{TOID}"}}"#));
+
+        let id: GlobalId = TOID.parse().unwrap();
+        assert_eq!(id.prefix().as_str(), "Tst");
+        assert!(id.to_oid::<Tst>().is_ok());
+        assert!(id.to_oid::<Frm>().is_err());
+        assert_eq!(GlobalId::from(id.to_oid::<Tst>().unwrap()), id);
+        assert_eq!(
+            OidStr::try_from(id).unwrap(),
+            TOID.parse::<OidStr>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn global_id_format() {
+        let oid: Oid<Evt> = Oid::with_uuid(*TOID.parse::<Oid<Tst>>().unwrap().uuid());
+        let id = GlobalId::from(oid);
+        assert_eq!(id.as_str(), "Evt_067q1t0e95fhzr006agjgjxrc3");
+        assert_eq!(id.as_str(), oid.to_string());
+        assert_eq!(id.to_oid::<Evt>().unwrap(), oid);
+        assert!(id.to_oid::<Tst>().is_err());
+
+        let res = execute(&format!(r#"{{ node(id: "{id}") }}"#)).await;
+        assert!(res.errors.is_empty(), "{:?}", res.errors);
+        assert_eq!(res.data.to_string(), format!(r#"{{node: "{id}"}}"#));
+    }
+
+    #[test]
+    fn global_id_invalid() {
+        assert_eq!("Tst".parse::<GlobalId>(), Err(Error::MissingSeparator));
+        assert_eq!(
+            "-0OUS781P4LU7V000PA2A2BN1GC".parse::<GlobalId>(),
+            Err(Error::MissingPrefix)
+        );
+        assert_eq!("Tst_".parse::<GlobalId>(), Err(Error::MissingValue));
+        let id: GlobalId = "Tst-nope".parse().unwrap();
+        assert!(id.to_oid::<Tst>().is_err());
+    }
+}
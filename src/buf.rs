@@ -0,0 +1,176 @@
+use core::{fmt, ops::Deref};
+
+#[cfg(feature = "alloc")]
+use smallvec::SmallVec;
+
+#[cfg(not(feature = "alloc"))]
+use crate::inline::InlineBytes;
+
+/// The number of bytes of an [`OidBuf`] stored inline
+const INLINE_LEN: usize = 64;
+
+#[cfg(feature = "alloc")]
+type BufBytes = SmallVec<[u8; INLINE_LEN]>;
+#[cfg(not(feature = "alloc"))]
+type BufBytes = InlineBytes<INLINE_LEN>;
+
+/// A stack allocated buffer holding the string representation of a TOID, i.e.
+/// `PREFIX-VALUE`
+///
+/// Created with [`Oid::to_buf`](crate::Oid::to_buf) or
+/// [`OidStr::to_buf`](crate::OidStr::to_buf). The buffer can store a TOID of
+/// up to 64 bytes inline (i.e. a prefix of up to 37 bytes with the default
+/// encoding); a longer TOID will be "spilled" to the heap.
+///
+/// Without the `alloc` feature the TOID is limited to 64 bytes, and creating an
+/// `OidBuf` for a longer TOID panics.
+///
+/// # Examples
+///
+/// ```rust
+/// # use typed_oid::OidStr;
+/// let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+/// let buf = oid.to_buf();
+/// assert_eq!(&*buf, "TST-0OQPKOAADLRUJ000J7U2UGNS2G");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct OidBuf {
+    bytes: BufBytes,
+}
+
+impl OidBuf {
+    /// Writes the TOID produced by `write` into a new buffer
+    ///
+    /// # Panics
+    ///
+    /// Without the `alloc` feature, if the TOID doesn't fit in the buffer
+    pub(crate) fn build<F>(write: F) -> Self
+    where
+        F: FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    {
+        match Self::try_build(write) {
+            Some(buf) => buf,
+            None => panic!("TOID exceeds the {INLINE_LEN} byte capacity of OidBuf"),
+        }
+    }
+
+    /// Writes the TOID produced by `write` into a new buffer, or returns `None`
+    /// if it doesn't fit, which is only possible without the `alloc` feature
+    pub(crate) fn try_build<F>(write: F) -> Option<Self>
+    where
+        F: FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    {
+        let mut buf = Self {
+            bytes: BufBytes::new(),
+        };
+        // Writing to the buffer can only fail when it can't grow
+        write(&mut Writer(&mut buf.bytes)).ok()?;
+        Some(buf)
+    }
+
+    /// Get the string representation of the TOID
+    pub fn as_str(&self) -> &str {
+        // SAFETY: self.bytes must not contain any invalid UTF-8. We don't expose the
+        // inner byte array for manipulation, and the only way to write to it is via
+        // `fmt::Write::write_str` which only accepts valid UTF-8
+        unsafe { core::str::from_utf8_unchecked(&self.bytes) }
+    }
+}
+
+struct Writer<'a>(&'a mut BufBytes);
+
+impl fmt::Write for Writer<'_> {
+    #[cfg(feature = "alloc")]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.0.try_extend_from_slice(s.as_bytes()) {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
+
+impl Deref for OidBuf {
+    type Target = str;
+
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl AsRef<str> for OidBuf {
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl fmt::Display for OidBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
+impl fmt::Debug for OidBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Debug::fmt(self.as_str(), f) }
+}
+
+impl PartialEq<str> for OidBuf {
+    fn eq(&self, other: &str) -> bool { self.as_str() == other }
+}
+
+impl PartialEq<&str> for OidBuf {
+    fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+}
+
+#[cfg(test)]
+mod oid_buf_tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let buf = OidBuf::build(|w| {
+            w.write_str("TST")?;
+            w.write_char('-')?;
+            w.write_str("0OQPKOAADLRUJ000J7U2UGNS2G")
+        });
+        assert_eq!(buf, "TST-0OQPKOAADLRUJ000J7U2UGNS2G");
+        assert!(!buf.bytes.spilled());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn long_prefix_spills() {
+        let buf = OidBuf::build(|w| {
+            w.write_str("TestingTestingTestingTestingTestingTesting")?;
+            w.write_char('-')?;
+            w.write_str("0OQPKOAADLRUJ000J7U2UGNS2G")
+        });
+        assert_eq!(
+            buf,
+            "TestingTestingTestingTestingTestingTesting-0OQPKOAADLRUJ000J7U2UGNS2G"
+        );
+        assert!(buf.bytes.spilled());
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc"))]
+    fn long_prefix_try_build() {
+        let buf = OidBuf::try_build(|w| {
+            w.write_str("TestingTestingTestingTestingTestingTesting")?;
+            w.write_char('-')?;
+            w.write_str("0OQPKOAADLRUJ000J7U2UGNS2G")
+        });
+        assert_eq!(buf, None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "alloc"))]
+    #[should_panic]
+    fn long_prefix_panics() {
+        OidBuf::build(|w| {
+            w.write_str("TestingTestingTestingTestingTestingTesting")?;
+            w.write_char('-')?;
+            w.write_str("0OQPKOAADLRUJ000J7U2UGNS2G")
+        });
+    }
+}
@@ -0,0 +1,216 @@
+//! [`diesel`](::diesel) support for TOIDs
+//!
+//! An [`Oid`] can be stored as a Postgres `UUID`, as TOID text or as the 16
+//! UUID bytes, while an [`OidStr`] is stored as TOID text as its prefix isn't
+//! part of the type. Writing to SQLite requires the `diesel_sqlite` feature.
+
+use alloc::{string::String, vec::Vec};
+use std::io::Write;
+
+use ::diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    mysql::Mysql,
+    pg::Pg,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::{Binary, Text, Uuid as SqlUuid},
+};
+#[cfg(feature = "diesel_sqlite")]
+use ::diesel::sqlite::Sqlite;
+use uuid::Uuid;
+
+use crate::{Oid, OidPrefix, OidStr};
+
+/// Implements `ToSql` for the backends which collect binds as raw bytes (a
+/// blanket impl over those backends would conflict with the SQLite impls)
+macro_rules! impl_raw_bytes_to_sql {
+    ($($db:ty),*) => {$(
+        impl<P: OidPrefix> ToSql<Text, $db> for Oid<P> {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $db>) -> serialize::Result {
+                out.write_all(self.to_buf().as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<P: OidPrefix> ToSql<Binary, $db> for Oid<P> {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $db>) -> serialize::Result {
+                out.write_all(self.uuid().as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        impl ToSql<Text, $db> for OidStr {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $db>) -> serialize::Result {
+                out.write_all(self.to_buf().as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+    )*};
+}
+
+impl_raw_bytes_to_sql!(Mysql, Pg);
+
+impl<P: OidPrefix> ToSql<SqlUuid, Pg> for Oid<P> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Uuid as ToSql<SqlUuid, Pg>>::to_sql(self.uuid(), out)
+    }
+}
+
+impl<P: OidPrefix> FromSql<SqlUuid, Pg> for Oid<P> {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        <Uuid as FromSql<SqlUuid, Pg>>::from_sql(bytes).map(Self::with_uuid)
+    }
+}
+
+#[cfg(feature = "diesel_sqlite")]
+impl<P: OidPrefix> ToSql<Text, Sqlite> for Oid<P> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl<P, DB> FromSql<Text, DB> for Oid<P>
+where
+    P: OidPrefix,
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(String::from_sql(bytes)?.parse()?)
+    }
+}
+
+#[cfg(feature = "diesel_sqlite")]
+impl<P: OidPrefix> ToSql<Binary, Sqlite> for Oid<P> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.uuid().as_bytes().as_slice());
+        Ok(IsNull::No)
+    }
+}
+
+impl<P, DB> FromSql<Binary, DB> for Oid<P>
+where
+    P: OidPrefix,
+    DB: Backend,
+    Vec<u8>: FromSql<Binary, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(Self::with_uuid(Uuid::from_slice(&Vec::from_sql(bytes)?)?))
+    }
+}
+
+#[cfg(feature = "diesel_sqlite")]
+impl ToSql<Text, Sqlite> for OidStr {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for OidStr
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        Ok(String::from_sql(bytes)?.parse()?)
+    }
+}
+
+#[cfg(all(test, feature = "diesel_sqlite"))]
+mod diesel_tests {
+    use ::diesel::{prelude::*, result::Error as DieselError, sql_query};
+
+    use super::*;
+    use crate::Error;
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    ::diesel::table! {
+        toids (id) {
+            id -> Integer,
+            text -> Nullable<Text>,
+            blob -> Nullable<Binary>,
+            oidstr -> Nullable<Text>,
+        }
+    }
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+
+    fn connect() -> SqliteConnection {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        sql_query("CREATE TABLE toids (id INTEGER PRIMARY KEY, text TEXT, blob BLOB, oidstr TEXT)")
+            .execute(&mut conn)
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn oid_roundtrip() {
+        let mut conn = connect();
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        ::diesel::insert_into(toids::table)
+            .values((toids::id.eq(1), toids::text.eq(oid), toids::blob.eq(oid)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let (text, blob): (Option<Oid<Tst>>, Option<Oid<Tst>>) = toids::table
+            .select((toids::text, toids::blob))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(text, Some(oid));
+        assert_eq!(blob, Some(oid));
+
+        let (text, blob): (Option<String>, Option<Vec<u8>>) = toids::table
+            .select((toids::text, toids::blob))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(text.as_deref(), Some(TOID));
+        assert_eq!(blob.as_deref(), Some(&oid.uuid().as_bytes()[..]));
+    }
+
+    #[test]
+    fn oidstr_roundtrip() {
+        let mut conn = connect();
+        let oid: OidStr = TOID.parse().unwrap();
+        ::diesel::insert_into(toids::table)
+            .values((toids::id.eq(1), toids::oidstr.eq(&oid)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let found: Option<OidStr> = toids::table.select(toids::oidstr).first(&mut conn).unwrap();
+        assert_eq!(found, Some(oid));
+    }
+
+    #[test]
+    fn unexpected_prefix() {
+        let mut conn = connect();
+        ::diesel::insert_into(toids::table)
+            .values((
+                toids::id.eq(1),
+                toids::text.eq("Other-0OUS781P4LU7V000PA2A2BN1GC"),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+
+        let err = toids::table
+            .select(toids::text)
+            .first::<Option<Oid<Tst>>>(&mut conn)
+            .unwrap_err();
+        // Diesel wraps the error with the name of the field
+        let source = match &err {
+            DieselError::DeserializationError(field) => field.source(),
+            err => panic!("unexpected error {err:?}"),
+        };
+        assert_eq!(
+            source.and_then(|e| e.downcast_ref::<Error>()),
+            Some(&Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Other".parse().unwrap()
+            })
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Encodings for the value portion of an OID
+//!
+//! The value of an OID is an encoded UUID. By default [`Base32Hex`] is used,
+//! however any [`OidEncoding`] may be selected per prefix type via
+//! [`OidPrefix::encoding`](crate::OidPrefix::encoding), or for an
+//! [`OidStr`](crate::OidStr) via [`Format::encoding`](crate::Format::encoding).
+//!
+//! All built-in encodings are fixed width and use alphabets in ascending ASCII
+//! order, so for a given [`Case`] they preserve the sort order of the
+//! underlying UUID bytes when compared bytewise.
+
+use core::fmt;
+
+use uuid::Uuid;
+
+use crate::{
+    error::{Error, Result},
+    format::Case,
+    uuid::{uuid_from_str_b32h, uuid_to_str_b32h, VALUE_LEN},
+};
+
+/// The maximum length of a value encoded by any [`OidEncoding`], and thus the
+/// size of buffer required to encode a value without allocating
+pub const MAX_VALUE_LEN: usize = 32;
+
+/// An encoding of a UUID into the value portion of an OID
+///
+/// Implementations must be fixed width, i.e. every UUID encodes to exactly
+/// [`OidEncoding::value_len`] characters.
+pub trait OidEncoding: Sync {
+    /// A short human readable name for the encoding
+    fn name(&self) -> &'static str;
+
+    /// The number of characters of an encoded value
+    fn value_len(&self) -> usize;
+
+    /// Encode `uuid` into `buf` using the given [`Case`] where applicable,
+    /// returning the encoded string
+    ///
+    /// Encodings whose alphabets are case sensitive ignore `case`.
+    fn encode<'a>(&self, uuid: &Uuid, case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str;
+
+    /// Decode a value into a UUID
+    ///
+    /// Any indexes in returned errors must be relative to the start of `s`.
+    fn decode(&self, s: &str) -> Result<Uuid>;
+
+    /// A regular expression character class matching the characters of a
+    /// value encoded using the given [`Case`], e.g. `[0-9A-V]`
+    ///
+    /// This is used to describe values in generated schemas. The default
+    /// matches any ASCII alphanumeric character.
+    fn char_class(&self, case: Case) -> &'static str {
+        let _ = case;
+        "[0-9A-Za-z]"
+    }
+}
+
+impl fmt::Debug for dyn OidEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.name()) }
+}
+
+/// Decodes a value with `encoding`, checking for an empty value first
+pub(crate) fn decode_value(encoding: &dyn OidEncoding, s: &str) -> Result<Uuid> {
+    if s.is_empty() {
+        return Err(Error::MissingValue);
+    }
+    encoding.decode(s)
+}
+
+/// The base32hex encoding (base32 with the extended hex alphabet `0-9A-V`, see
+/// [RFC4648](https://datatracker.ietf.org/doc/html/rfc4648.html#section-7))
+/// without padding. This is the default encoding.
+///
+/// Values are 26 characters, decoding is case insensitive and preserves sort
+/// order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Base32Hex;
+
+impl OidEncoding for Base32Hex {
+    fn name(&self) -> &'static str { "base32hex" }
+
+    fn value_len(&self) -> usize { VALUE_LEN }
+
+    fn encode<'a>(&self, uuid: &Uuid, case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        let buf: &mut [u8; VALUE_LEN] = (&mut buf[..VALUE_LEN]).try_into().unwrap();
+        uuid_to_str_b32h(uuid, case, buf)
+    }
+
+    fn decode(&self, s: &str) -> Result<Uuid> { uuid_from_str_b32h(s) }
+
+    fn char_class(&self, case: Case) -> &'static str {
+        match case {
+            Case::Upper => "[0-9A-V]",
+            Case::Lower => "[0-9a-v]",
+        }
+    }
+}
+
+/// Crockford's base32 (alphabet `0-9A-HJKMNP-TV-Z`, see
+/// <https://www.crockford.com/base32.html>) as used by ULIDs and TypeIDs
+///
+/// Values are 26 characters where the first character is always `0-7` (the
+/// 128 bits are left padded with two zero bits). Decoding is case insensitive
+/// but the alternate symbols `I`, `L`, `O` and `U` are not accepted. Preserves
+/// sort order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Crockford;
+
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CROCKFORD_LEN: usize = 26;
+
+impl OidEncoding for Crockford {
+    fn name(&self) -> &'static str { "crockford" }
+
+    fn value_len(&self) -> usize { CROCKFORD_LEN }
+
+    fn encode<'a>(&self, uuid: &Uuid, case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        let n = uuid.as_u128();
+        for (i, c) in buf[..CROCKFORD_LEN].iter_mut().enumerate() {
+            *c = CROCKFORD_ALPHABET[((n >> (125 - 5 * i)) & 0x1F) as usize];
+        }
+        case.apply(&mut buf[..CROCKFORD_LEN]);
+        ascii_str(&buf[..CROCKFORD_LEN])
+    }
+
+    fn decode(&self, s: &str) -> Result<Uuid> {
+        let bytes = check_len(s, CROCKFORD_LEN)?;
+        let mut n: u128 = 0;
+        for (i, &c) in bytes.iter().enumerate() {
+            let v = lookup(CROCKFORD_ALPHABET, c.to_ascii_uppercase())
+                .filter(|&v| i != 0 || v < 8)
+                .ok_or_else(|| invalid_char(s, i))?;
+            n = (n << 5) | v as u128;
+        }
+        Ok(Uuid::from_u128(n))
+    }
+
+    fn char_class(&self, case: Case) -> &'static str {
+        match case {
+            Case::Upper => "[0-9A-HJKMNP-TV-Z]",
+            Case::Lower => "[0-9a-hjkmnp-tv-z]",
+        }
+    }
+}
+
+/// Base58 using the Bitcoin alphabet (`1-9A-HJ-NP-Za-km-z`)
+///
+/// Values are left padded with `1` (the zero symbol) to a fixed width of 22
+/// characters, which preserves sort order. Decoding is case sensitive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Base58;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE58_LEN: usize = 22;
+
+impl OidEncoding for Base58 {
+    fn name(&self) -> &'static str { "base58" }
+
+    fn value_len(&self) -> usize { BASE58_LEN }
+
+    fn encode<'a>(&self, uuid: &Uuid, _case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        encode_radix(uuid.as_u128(), BASE58_ALPHABET, &mut buf[..BASE58_LEN])
+    }
+
+    fn decode(&self, s: &str) -> Result<Uuid> { decode_radix(s, BASE58_ALPHABET, BASE58_LEN) }
+
+    fn char_class(&self, _case: Case) -> &'static str { "[1-9A-HJ-NP-Za-km-z]" }
+}
+
+/// Base62 using the alphabet `0-9A-Za-z`
+///
+/// Values are left padded with `0` to a fixed width of 22 characters, which
+/// preserves sort order. Decoding is case sensitive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Base62;
+
+const BASE62_ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE62_LEN: usize = 22;
+
+impl OidEncoding for Base62 {
+    fn name(&self) -> &'static str { "base62" }
+
+    fn value_len(&self) -> usize { BASE62_LEN }
+
+    fn encode<'a>(&self, uuid: &Uuid, _case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        encode_radix(uuid.as_u128(), BASE62_ALPHABET, &mut buf[..BASE62_LEN])
+    }
+
+    fn decode(&self, s: &str) -> Result<Uuid> { decode_radix(s, BASE62_ALPHABET, BASE62_LEN) }
+
+    fn char_class(&self, _case: Case) -> &'static str { "[0-9A-Za-z]" }
+}
+
+/// Plain hexadecimal (`0-9A-F`) without hyphens
+///
+/// Values are 32 characters, decoding is case insensitive and preserves sort
+/// order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Hex;
+
+const HEX_ALPHABET: &[u8; 16] = b"0123456789ABCDEF";
+const HEX_LEN: usize = 32;
+
+impl OidEncoding for Hex {
+    fn name(&self) -> &'static str { "hex" }
+
+    fn value_len(&self) -> usize { HEX_LEN }
+
+    fn encode<'a>(&self, uuid: &Uuid, case: Case, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        for (i, b) in uuid.as_bytes().iter().enumerate() {
+            buf[2 * i] = HEX_ALPHABET[(b >> 4) as usize];
+            buf[2 * i + 1] = HEX_ALPHABET[(b & 0xF) as usize];
+        }
+        case.apply(&mut buf[..HEX_LEN]);
+        ascii_str(&buf[..HEX_LEN])
+    }
+
+    fn decode(&self, s: &str) -> Result<Uuid> {
+        let bytes = check_len(s, HEX_LEN)?;
+        let mut n: u128 = 0;
+        for (i, &c) in bytes.iter().enumerate() {
+            let v = lookup(HEX_ALPHABET, c.to_ascii_uppercase()).ok_or_else(|| invalid_char(s, i))?;
+            n = (n << 4) | v as u128;
+        }
+        Ok(Uuid::from_u128(n))
+    }
+
+    fn char_class(&self, case: Case) -> &'static str {
+        match case {
+            Case::Upper => "[0-9A-F]",
+            Case::Lower => "[0-9a-f]",
+        }
+    }
+}
+
+/// Returns the bytes of `s` if it is exactly `len` bytes long
+fn check_len(s: &str, len: usize) -> Result<&[u8]> {
+    if s.len() != len {
+        return Err(Error::WrongValueLength {
+            expected: len,
+            found: s.len(),
+        });
+    }
+    Ok(s.as_bytes())
+}
+
+/// The index of `c` in `alphabet`
+fn lookup(alphabet: &[u8], c: u8) -> Option<u8> {
+    alphabet.iter().position(|&a| a == c).map(|v| v as u8)
+}
+
+fn invalid_char(s: &str, index: usize) -> Error {
+    Error::InvalidValueChar {
+        index,
+        char: s
+            .get(index..)
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER),
+    }
+}
+
+/// Views the encoded bytes as a `str`
+fn ascii_str(buf: &[u8]) -> &str {
+    debug_assert!(buf.is_ascii());
+    // SAFETY: Every byte was taken from one of the alphabets above, all of which are
+    // 7-bit ASCII which itself is a subset of UTF-8
+    unsafe { core::str::from_utf8_unchecked(buf) }
+}
+
+/// Encodes `n` in the base of `alphabet` left padded to the length of `out`
+fn encode_radix<'a>(mut n: u128, alphabet: &[u8], out: &'a mut [u8]) -> &'a str {
+    let base = alphabet.len() as u128;
+    for c in out.iter_mut().rev() {
+        *c = alphabet[(n % base) as usize];
+        n /= base;
+    }
+    ascii_str(out)
+}
+
+fn decode_radix(s: &str, alphabet: &[u8], len: usize) -> Result<Uuid> {
+    let bytes = check_len(s, len)?;
+    let base = alphabet.len() as u128;
+    let mut n: u128 = 0;
+    for (i, &c) in bytes.iter().enumerate() {
+        let v = lookup(alphabet, c).ok_or_else(|| invalid_char(s, i))?;
+        n = n
+            .checked_mul(base)
+            .and_then(|n| n.checked_add(v as u128))
+            .ok_or(Error::ValueOutOfRange)?;
+    }
+    Ok(Uuid::from_u128(n))
+}
+
+#[cfg(test)]
+mod encoding_tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const UUID: &str = "063dc3a0-3925-7c7f-8000-ca84a12ee183";
+
+    fn encode(encoding: &dyn OidEncoding, uuid: &Uuid, case: Case) -> String {
+        let mut buf = [0; MAX_VALUE_LEN];
+        encoding.encode(uuid, case, &mut buf).to_owned()
+    }
+
+    #[test]
+    fn known_values() {
+        let uuid: Uuid = UUID.parse().unwrap();
+        assert_eq!(
+            encode(&Base32Hex, &uuid, Case::Upper),
+            "0OUS781P4LU7V000PA2A2BN1GC"
+        );
+        assert_eq!(
+            encode(&Crockford, &uuid, Case::Upper),
+            "067Q1T0E95FHZR006AGJGJXRC3"
+        );
+        assert_eq!(
+            encode(&Crockford, &uuid, Case::Lower),
+            "067q1t0e95fhzr006agjgjxrc3"
+        );
+        assert_eq!(
+            encode(&Hex, &uuid, Case::Lower),
+            "063dc3a039257c7f8000ca84a12ee183"
+        );
+        assert_eq!(encode(&Base58, &Uuid::nil(), Case::Upper).len(), 22);
+        assert_eq!(
+            encode(&Base58, &Uuid::nil(), Case::Upper),
+            "1111111111111111111111"
+        );
+        assert_eq!(
+            encode(&Base62, &Uuid::nil(), Case::Upper),
+            "0000000000000000000000"
+        );
+        assert_eq!(
+            encode(&Base62, &Uuid::max(), Case::Upper),
+            "7n42DGM5Tflk9n8mt7Fhc7"
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        for encoding in ENCODINGS {
+            assert_eq!(
+                encoding.decode("0"),
+                Err(Error::WrongValueLength {
+                    expected: encoding.value_len(),
+                    found: 1
+                }),
+                "{encoding:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_char() {
+        assert_eq!(
+            Crockford.decode("067Q1T0E95FHZR00IAGJGJXRC3"),
+            Err(Error::InvalidValueChar {
+                index: 16,
+                char: 'I'
+            })
+        );
+        assert_eq!(
+            Hex.decode("063dc3a039257c7f8000ca84a12ee18g"),
+            Err(Error::InvalidValueChar {
+                index: 31,
+                char: 'g'
+            })
+        );
+        assert_eq!(
+            Base58.decode("0111111111111111111111"),
+            Err(Error::InvalidValueChar {
+                index: 0,
+                char: '0'
+            })
+        );
+    }
+
+    #[test]
+    fn crockford_leading_bits() {
+        assert!(Crockford.decode("7ZZZZZZZZZZZZZZZZZZZZZZZZZ").is_ok());
+        assert_eq!(
+            Crockford.decode("8ZZZZZZZZZZZZZZZZZZZZZZZZZ"),
+            Err(Error::InvalidValueChar {
+                index: 0,
+                char: '8'
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            Base62.decode("zzzzzzzzzzzzzzzzzzzzzz"),
+            Err(Error::ValueOutOfRange)
+        );
+        assert_eq!(
+            Base58.decode("zzzzzzzzzzzzzzzzzzzzzz"),
+            Err(Error::ValueOutOfRange)
+        );
+    }
+
+    const ENCODINGS: [&dyn OidEncoding; 5] = [&Base32Hex, &Crockford, &Base58, &Base62, &Hex];
+
+    proptest! {
+        #[test]
+        fn roundtrip(bytes in any::<[u8; 16]>(), lower in any::<bool>()) {
+            let uuid = Uuid::from_bytes(bytes);
+            let case = if lower { Case::Lower } else { Case::Upper };
+            for encoding in ENCODINGS {
+                let encoded = encode(encoding, &uuid, case);
+                prop_assert_eq!(encoded.len(), encoding.value_len());
+                prop_assert_eq!(encoding.decode(&encoded), Ok(uuid));
+            }
+        }
+
+        #[test]
+        fn preserves_sort_order(a in any::<[u8; 16]>(), b in any::<[u8; 16]>(), lower in any::<bool>()) {
+            let (a, b) = (Uuid::from_bytes(a), Uuid::from_bytes(b));
+            let case = if lower { Case::Lower } else { Case::Upper };
+            for encoding in ENCODINGS {
+                prop_assert_eq!(
+                    encode(encoding, &a, case).cmp(&encode(encoding, &b, case)),
+                    a.as_bytes().cmp(b.as_bytes()),
+                    "{:?}", encoding
+                );
+            }
+        }
+
+        #[test]
+        fn decode_never_panics(s in "\\PC{0,34}") {
+            for encoding in ENCODINGS {
+                let _ = encoding.decode(&s);
+            }
+        }
+    }
+}
@@ -1,29 +1,178 @@
 //! Defines the convenience [`Result`] type and [`Error`] type
 
-use std::result::Result as StdResult;
+use core::result::Result as StdResult;
+
+use crate::prefix::Prefix;
 
 /// A convenience type for results where the `E` is a
 /// `seapalne_oid::error::Error`
 pub type Result<T> = StdResult<T, Error>;
 
 /// Errors that can be returned by this crate
+///
+/// All indexes are byte offsets relative to the start of the full input string
+/// being parsed (e.g. `TST-...`), not just the portion containing the error.
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
     #[error("wrong number of bytes to construct Prefix")]
     PrefixByteLength,
-    #[error("prefix characters may only be 7-bit ASCII values of 2-7,a-z,A-Z")]
+    #[error("prefix characters may only be 7-bit ASCII values of 0-9,a-z,A-Z")]
     InvalidPrefix {
         /// The index of the first invalid character
         valid_until: usize,
     },
+    #[error("expected prefix `{expected}` but found `{found}`")]
+    UnexpectedPrefix {
+        /// The prefix of the type being parsed
+        expected: &'static str,
+        /// The (valid, but unexpected) prefix that was found
+        found: Prefix,
+    },
     #[error("attempted to deserialize OID without a prefix")]
     MissingPrefix,
     #[error("deserialize OID without a separator")]
     MissingSeparator,
+    #[error("OID separator {separator:?} must not be ASCII alphanumeric")]
+    InvalidSeparator {
+        /// The invalid separator
+        separator: char,
+    },
     #[error("attempted to deserialize OID without a value")]
     MissingValue,
+    #[error("OID value must be {expected} characters long but found {found}")]
+    WrongValueLength {
+        /// The required length of the value
+        expected: usize,
+        /// The length of the value that was found
+        found: usize,
+    },
+    #[error("invalid character {char:?} at index {index} of OID value")]
+    InvalidValueChar {
+        /// The index of the invalid character
+        index: usize,
+        /// The invalid character
+        char: char,
+    },
+    #[error("OID value is out of range for a UUID")]
+    ValueOutOfRange,
+    #[error("record key must be a UUID or a string holding an OID value or UUID")]
+    InvalidRecordKey,
     #[error("UUID error: {0}")]
-    Uuid(#[from] uuid::Error),
-    #[error("base32hex Decode error: {0}")]
-    Base32Decode(#[from] data_encoding::DecodeError),
+    // Older versions of uuid only implement `Error` with their `std` feature
+    Uuid(#[cfg_attr(feature = "std", source)] uuid::Error),
+}
+
+impl From<uuid::Error> for Error {
+    fn from(e: uuid::Error) -> Self { Error::Uuid(e) }
+}
+
+impl Error {
+    /// Shifts any indexes contained in the error by `by` bytes, used when the
+    /// erroneous string was a sub-slice of the full input
+    pub(crate) fn offset(self, by: usize) -> Self {
+        match self {
+            Error::InvalidPrefix { valid_until } => Error::InvalidPrefix {
+                valid_until: valid_until + by,
+            },
+            Error::InvalidValueChar { index, char } => Error::InvalidValueChar {
+                index: index + by,
+                char,
+            },
+            e => e,
+        }
+    }
+}
+
+#[cfg(feature = "miette")]
+#[cfg_attr(docsrs, doc(cfg(feature = "miette")))]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self {
+            Error::PrefixByteLength => "typed_oid::prefix_byte_length",
+            Error::InvalidPrefix { .. } => "typed_oid::invalid_prefix",
+            Error::UnexpectedPrefix { .. } => "typed_oid::unexpected_prefix",
+            Error::MissingPrefix => "typed_oid::missing_prefix",
+            Error::MissingSeparator => "typed_oid::missing_separator",
+            Error::InvalidSeparator { .. } => "typed_oid::invalid_separator",
+            Error::MissingValue => "typed_oid::missing_value",
+            Error::WrongValueLength { .. } => "typed_oid::wrong_value_length",
+            Error::InvalidValueChar { .. } => "typed_oid::invalid_value_char",
+            Error::ValueOutOfRange => "typed_oid::value_out_of_range",
+            Error::InvalidRecordKey => "typed_oid::invalid_record_key",
+            Error::Uuid(_) => "typed_oid::uuid",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        match self {
+            Error::UnexpectedPrefix { expected, .. } => {
+                Some(Box::new(format!("use the prefix `{expected}`")))
+            }
+            Error::MissingSeparator => Some(Box::new(
+                "OIDs are of the form `PREFIX-VALUE`, e.g. `TST-0OUS781P4LU7V000PA2A2BN1GC`",
+            )),
+            Error::WrongValueLength { .. } | Error::InvalidValueChar { .. } => Some(Box::new(
+                "the value must be an encoded UUID, by default base32hex (0-9,A-V) without \
+                 padding",
+            )),
+            _ => None,
+        }
+    }
+
+    /// Labels the offending portion of the input. Attach the input with
+    /// [`miette::Report::with_source_code`] to render them.
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let label = match self {
+            Error::InvalidPrefix { valid_until } => {
+                miette::LabeledSpan::at(*valid_until..*valid_until + 1, "invalid character")
+            }
+            Error::UnexpectedPrefix { expected, found } => miette::LabeledSpan::at(
+                0..found.as_str().len(),
+                format!("expected `{expected}`"),
+            ),
+            Error::InvalidValueChar { index, char } => {
+                miette::LabeledSpan::at(*index..*index + char.len_utf8(), "invalid character")
+            }
+            _ => return None,
+        };
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "miette")]
+mod miette_tests {
+    use miette::Diagnostic;
+
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let err = Error::InvalidValueChar {
+            index: 4,
+            char: '&',
+        };
+        let labels: Vec<_> = err.labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].offset(), 4);
+        assert_eq!(labels[0].len(), 1);
+
+        let err = Error::UnexpectedPrefix {
+            expected: "Tst",
+            found: "Frm".parse().unwrap(),
+        };
+        let labels: Vec<_> = err.labels().unwrap().collect();
+        assert_eq!(labels[0].offset(), 0);
+        assert_eq!(labels[0].len(), 3);
+        assert_eq!(labels[0].label(), Some("expected `Tst`"));
+    }
+
+    #[test]
+    fn code() {
+        assert_eq!(
+            Error::MissingValue.code().unwrap().to_string(),
+            "typed_oid::missing_value"
+        );
+    }
 }
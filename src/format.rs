@@ -0,0 +1,166 @@
+use core::fmt;
+
+use crate::{
+    encoding::{Base32Hex, OidEncoding},
+    error::{Error, Result},
+    OidStr,
+};
+
+/// The letter case used when rendering the value portion of an OID
+///
+/// Parsing always accepts either case, however [`Case::Upper`] is the canonical
+/// form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// Render the value using upper case letters, e.g.
+    /// `TST-0OUS781P4LU7V000PA2A2BN1GC`
+    Upper,
+    /// Render the value using lower case letters, e.g.
+    /// `TST-0ous781p4lu7v000pa2a2bn1gc`
+    Lower,
+}
+
+impl Default for Case {
+    fn default() -> Self { Case::Upper }
+}
+
+impl Case {
+    /// Converts the ASCII letters of `buf` to this case in place
+    pub(crate) fn apply(self, buf: &mut [u8]) {
+        match self {
+            Case::Upper => buf.make_ascii_uppercase(),
+            Case::Lower => buf.make_ascii_lowercase(),
+        }
+    }
+}
+
+/// Options controlling how an [`OidStr`] is rendered or parsed
+///
+/// For [`Oid<P>`](crate::Oid) these options are instead controlled by the
+/// [`OidPrefix`](crate::OidPrefix) implementation of `P`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use typed_oid::{Case, Format, OidStr};
+/// let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+/// let lower = Format::new().value_case(Case::Lower);
+/// assert_eq!(
+///     oid.display_with(lower).to_string(),
+///     "TST-0oqpkoaadlruj000j7u2ugns2g"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Format {
+    pub(crate) value_case: Case,
+    pub(crate) encoding: &'static dyn OidEncoding,
+    pub(crate) separator: char,
+    pub(crate) alt_separator: Option<char>,
+}
+
+impl Format {
+    /// Create the default (canonical) format
+    pub const fn new() -> Self {
+        Self {
+            value_case: Case::Upper,
+            encoding: &Base32Hex,
+            separator: '-',
+            alt_separator: None,
+        }
+    }
+
+    /// Set the case used to render the value portion of the OID
+    pub const fn value_case(mut self, case: Case) -> Self {
+        self.value_case = case;
+        self
+    }
+
+    /// Set the [`OidEncoding`] of the value portion of the OID, which is
+    /// [`Base32Hex`] by default
+    pub const fn encoding(mut self, encoding: &'static dyn OidEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Set the separator between the prefix and value portions of the OID,
+    /// which is `-` by default
+    ///
+    /// The separator must not be a character which can appear in the prefix or
+    /// value, i.e. it can't be ASCII alphanumeric.
+    ///
+    /// # Panics
+    ///
+    /// If `separator` is ASCII alphanumeric
+    pub const fn separator(mut self, separator: char) -> Self {
+        assert!(
+            !separator.is_ascii_alphanumeric(),
+            "OID separator must not be ASCII alphanumeric"
+        );
+        self.separator = separator;
+        self
+    }
+
+    /// Set an additional separator which is accepted (but never rendered) when
+    /// parsing, e.g. the previous separator while transitioning to a new one
+    ///
+    /// # Panics
+    ///
+    /// If `separator` is ASCII alphanumeric
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Format, OidStr};
+    /// let format = Format::new().separator('_').alt_separator('-');
+    /// let old = OidStr::parse_with("TST-0OQPKOAADLRUJ000J7U2UGNS2G", format).unwrap();
+    /// let new = OidStr::parse_with("TST_0OQPKOAADLRUJ000J7U2UGNS2G", format).unwrap();
+    /// assert_eq!(old, new);
+    /// assert_eq!(
+    ///     old.display_with(format).to_string(),
+    ///     "TST_0OQPKOAADLRUJ000J7U2UGNS2G"
+    /// );
+    /// ```
+    pub const fn alt_separator(mut self, separator: char) -> Self {
+        assert!(
+            !separator.is_ascii_alphanumeric(),
+            "OID separator must not be ASCII alphanumeric"
+        );
+        self.alt_separator = Some(separator);
+        self
+    }
+}
+
+/// Checks that neither separator could appear in a prefix or value, which
+/// would otherwise split the OID in the wrong place
+pub(crate) fn check_separators(separator: char, alt_separator: Option<char>) -> Result<()> {
+    match Some(separator).into_iter().chain(alt_separator).find(char::is_ascii_alphanumeric) {
+        Some(separator) => Err(Error::InvalidSeparator { separator }),
+        None => Ok(()),
+    }
+}
+
+/// Splits `s` at the first `separator` or `alt_separator`, returning the prefix,
+/// the value and the byte offset of the value within `s`
+pub(crate) fn split_separator(
+    s: &str,
+    separator: char,
+    alt_separator: Option<char>,
+) -> Option<(&str, &str, usize)> {
+    let (pfx, val) = s.split_once(|c| c == separator || Some(c) == alt_separator)?;
+    Some((pfx, val, s.len() - val.len()))
+}
+
+impl Default for Format {
+    fn default() -> Self { Self::new() }
+}
+
+/// Renders an [`OidStr`] with a given [`Format`], returned by
+/// [`OidStr::display_with`]
+pub struct Formatted<'a> {
+    pub(crate) oid: &'a OidStr,
+    pub(crate) format: Format,
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.oid.write_to_with(f, self.format) }
+}
@@ -0,0 +1,279 @@
+//! Monotonic UUIDv7 TOID generation
+//!
+//! [`Oid::new_v7_now`] creates independent UUIDv7s, so TOIDs created within
+//! the same millisecond (or after the system clock moves backwards) aren't
+//! necessarily ordered by creation. The generators here instead guarantee that
+//! every TOID is strictly greater than the last one they generated, following
+//! the monotonic random method of
+//! [RFC 9562 §6.2](https://www.rfc-editor.org/rfc/rfc9562.html#section-6.2).
+//!
+//! When the current time is not after the last generated TOID (i.e. within the
+//! same millisecond, or the clock has regressed) the last timestamp is held and
+//! the random bits are incremented instead, which carries into the timestamp
+//! if they overflow.
+
+use core::cell::Cell;
+use std::sync::{Mutex, PoisonError};
+
+use uuid::{
+    timestamp::{context::NoContext, Timestamp},
+    Uuid,
+};
+
+use crate::{error::Result, Error, Oid, OidPrefix, OidStr, Prefix};
+
+/// The number of random bits following the timestamp of a UUIDv7
+const RAND_BITS: u32 = 74;
+/// The number of bits of `rand_b`
+const RAND_B_BITS: u32 = 62;
+const RAND_B_MASK: u128 = (1 << RAND_B_BITS) - 1;
+const RAND_A_MASK: u128 = 0xFFF;
+/// The version and variant bits of a UUIDv7
+const VERSION_VARIANT: u128 = (0x7 << 76) | (0b10 << 62);
+
+/// A thread-safe generator of strictly increasing UUIDv7 TOIDs
+///
+/// TOIDs are strictly increasing across all threads using the same generator,
+/// so a single generator (e.g. in a `static`) guarantees ordering per process.
+///
+/// # Examples
+///
+/// ```rust
+/// # use typed_oid::{Oid, OidGenerator, OidPrefix};
+/// struct Usr;
+/// impl OidPrefix for Usr {}
+///
+/// static OIDS: OidGenerator = OidGenerator::new();
+///
+/// let a: Oid<Usr> = OIDS.generate();
+/// let b: Oid<Usr> = OIDS.generate();
+/// assert!(a < b);
+///
+/// let c = OIDS.generate_str("Ord").unwrap();
+/// assert!(c.uuid() > b.uuid());
+/// ```
+#[derive(Debug, Default)]
+pub struct OidGenerator {
+    last: Mutex<u128>,
+    sub_millis: bool,
+}
+
+// `Mutex::new` is only `const` since 1.63, see the MSRV of the `uuid_v7` feature
+#[clippy::msrv = "1.63.0"]
+impl OidGenerator {
+    /// Create a new generator
+    pub const fn new() -> Self {
+        Self {
+            last: Mutex::new(0),
+            sub_millis: false,
+        }
+    }
+
+    /// Create a new generator which stores sub-millisecond precision of the
+    /// timestamp in the 12 bits of `rand_a`, leaving fewer random bits
+    pub const fn with_sub_millis() -> Self {
+        Self {
+            last: Mutex::new(0),
+            sub_millis: true,
+        }
+    }
+
+    /// Generate the next UUID
+    pub fn next_uuid(&self) -> Uuid {
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+        *last = next_packed(*last, self.sub_millis);
+        unpack(*last)
+    }
+
+    /// Generate the next TOID for `P`
+    pub fn generate<P: OidPrefix>(&self) -> Oid<P> { Oid::with_uuid(self.next_uuid()) }
+
+    /// Generate the next OID with the given [`Prefix`]
+    pub fn generate_str<P>(&self, prefix: P) -> Result<OidStr>
+    where
+        P: TryInto<Prefix, Error = Error>,
+    {
+        OidStr::with_uuid(prefix, self.next_uuid())
+    }
+}
+
+/// A single-threaded generator of strictly increasing UUIDv7 TOIDs
+///
+/// This avoids the locking of [`OidGenerator`] and is intended to be used with
+/// `thread_local!`, in which case TOIDs are strictly increasing per thread.
+///
+/// # Examples
+///
+/// ```rust
+/// # use typed_oid::{LocalOidGenerator, Oid, OidPrefix};
+/// struct Usr;
+/// impl OidPrefix for Usr {}
+///
+/// thread_local! {
+///     static OIDS: LocalOidGenerator = LocalOidGenerator::new();
+/// }
+///
+/// let a: Oid<Usr> = OIDS.with(LocalOidGenerator::generate);
+/// let b: Oid<Usr> = OIDS.with(LocalOidGenerator::generate);
+/// assert!(a < b);
+/// ```
+#[derive(Debug, Default)]
+pub struct LocalOidGenerator {
+    last: Cell<u128>,
+    sub_millis: bool,
+}
+
+impl LocalOidGenerator {
+    /// Create a new generator
+    pub const fn new() -> Self {
+        Self {
+            last: Cell::new(0),
+            sub_millis: false,
+        }
+    }
+
+    /// Create a new generator which stores sub-millisecond precision of the
+    /// timestamp in the 12 bits of `rand_a`, leaving fewer random bits
+    pub const fn with_sub_millis() -> Self {
+        Self {
+            last: Cell::new(0),
+            sub_millis: true,
+        }
+    }
+
+    /// Generate the next UUID
+    pub fn next_uuid(&self) -> Uuid {
+        let last = next_packed(self.last.get(), self.sub_millis);
+        self.last.set(last);
+        unpack(last)
+    }
+
+    /// Generate the next TOID for `P`
+    pub fn generate<P: OidPrefix>(&self) -> Oid<P> { Oid::with_uuid(self.next_uuid()) }
+
+    /// Generate the next OID with the given [`Prefix`]
+    pub fn generate_str<P>(&self, prefix: P) -> Result<OidStr>
+    where
+        P: TryInto<Prefix, Error = Error>,
+    {
+        OidStr::with_uuid(prefix, self.next_uuid())
+    }
+}
+
+/// The next value after `last` for the current time, where values are the
+/// timestamp and random bits of a UUIDv7 without the version and variant bits
+fn next_packed(last: u128, sub_millis: bool) -> u128 {
+    let ts = Timestamp::now(NoContext);
+    let mut uuid = Uuid::new_v7(ts).as_u128();
+    if sub_millis {
+        let (_, nanos) = ts.to_unix();
+        let fraction = u128::from(nanos % 1_000_000) * 4096 / 1_000_000;
+        uuid = (uuid & !(RAND_A_MASK << 64)) | (fraction << 64);
+    }
+    next_after(last, pack(uuid))
+}
+
+/// Picks `candidate` if it is after `last`, otherwise holds the timestamp of
+/// `last` and increments its random bits
+fn next_after(last: u128, candidate: u128) -> u128 {
+    if candidate > last {
+        candidate
+    } else {
+        last + 1
+    }
+}
+
+fn pack(uuid: u128) -> u128 {
+    let ts = uuid >> 80;
+    let rand_a = (uuid >> 64) & RAND_A_MASK;
+    (ts << RAND_BITS) | (rand_a << RAND_B_BITS) | (uuid & RAND_B_MASK)
+}
+
+fn unpack(packed: u128) -> Uuid {
+    let ts = packed >> RAND_BITS;
+    let rand_a = (packed >> RAND_B_BITS) & RAND_A_MASK;
+    Uuid::from_u128((ts << 80) | (rand_a << 64) | VERSION_VARIANT | (packed & RAND_B_MASK))
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use uuid::Version;
+
+    use super::*;
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    fn packed(uuid: &str) -> u128 { pack(Uuid::parse_str(uuid).unwrap().as_u128()) }
+
+    #[test]
+    fn pack_roundtrip() {
+        let uuid = Uuid::parse_str("063dc3a0-3925-7c7f-8000-ca84a12ee183").unwrap();
+        assert_eq!(unpack(pack(uuid.as_u128())), uuid);
+    }
+
+    #[test]
+    fn holds_timestamp_on_regression() {
+        let last = packed("063dc3a0-3925-7c7f-8000-ca84a12ee183");
+        let earlier = packed("063dc3a0-3924-7fff-bfff-ffffffffffff");
+        let next = unpack(next_after(last, earlier));
+        assert_eq!(next.to_string(), "063dc3a0-3925-7c7f-8000-ca84a12ee184");
+        assert_eq!(next_after(last, last + 5), last + 5);
+    }
+
+    #[test]
+    fn carries_into_timestamp() {
+        let last = packed("063dc3a0-3925-7fff-bfff-ffffffffffff");
+        let next = unpack(next_after(last, 0));
+        assert_eq!(next.to_string(), "063dc3a0-3926-7000-8000-000000000000");
+        assert_eq!(next.get_version(), Some(Version::SortRand));
+    }
+
+    #[test]
+    fn strictly_increasing() {
+        for generator in [OidGenerator::new(), OidGenerator::with_sub_millis()] {
+            let mut last: Oid<Tst> = generator.generate();
+            for _ in 0..10_000 {
+                let next = generator.generate();
+                assert!(next > last);
+                assert_eq!(next.uuid().get_version(), Some(Version::SortRand));
+                last = next;
+            }
+        }
+
+        let generator = LocalOidGenerator::new();
+        let mut last = generator.generate_str("Tst").unwrap();
+        for _ in 0..10_000 {
+            let next = generator.generate_str("Tst").unwrap();
+            assert!(next > last);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn strictly_increasing_across_threads() {
+        static GENERATOR: OidGenerator = OidGenerator::new();
+
+        let mut oids: Vec<Oid<Tst>> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| (0..1_000).map(|_| GENERATOR.generate()).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+        oids.sort();
+        oids.dedup();
+        assert_eq!(oids.len(), 4_000);
+    }
+
+    #[test]
+    fn invalid_prefix() {
+        let generator = OidGenerator::new();
+        assert_eq!(
+            generator.generate_str("Tst-").unwrap_err(),
+            Error::InvalidPrefix { valid_until: 3 }
+        );
+    }
+}
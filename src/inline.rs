@@ -0,0 +1,88 @@
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A fixed capacity byte buffer used in place of `SmallVec` when the `alloc`
+/// feature is disabled, and thus can't spill to the heap
+#[derive(Clone, Copy)]
+pub(crate) struct InlineBytes<const N: usize> {
+    len: usize,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> InlineBytes<N> {
+    pub(crate) const fn new() -> Self { Self { len: 0, bytes: [0; N] } }
+
+    /// Create a buffer holding `slice`
+    ///
+    /// # Panics
+    ///
+    /// If `slice` is longer than the capacity `N`
+    pub(crate) fn from_slice(slice: &[u8]) -> Self {
+        let mut buf = Self::new();
+        assert!(
+            buf.try_extend_from_slice(slice),
+            "slice of {} bytes exceeds the inline capacity of {} bytes",
+            slice.len(),
+            N
+        );
+        buf
+    }
+
+    /// Append `slice` to the buffer, returning `false` (and leaving the buffer
+    /// unchanged) if it doesn't fit
+    pub(crate) fn try_extend_from_slice(&mut self, slice: &[u8]) -> bool {
+        match self.bytes.get_mut(self.len..self.len + slice.len()) {
+            Some(bytes) => bytes.copy_from_slice(slice),
+            None => return false,
+        }
+        self.len += slice.len();
+        true
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] { &self.bytes[..self.len] }
+
+    /// Mirrors `SmallVec::spilled`, an inline buffer never spills
+    #[cfg(test)]
+    pub(crate) fn spilled(&self) -> bool { false }
+}
+
+impl<const N: usize> Deref for InlineBytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] { self.as_slice() }
+}
+
+impl<const N: usize> PartialEq for InlineBytes<N> {
+    fn eq(&self, other: &Self) -> bool { self.as_slice() == other.as_slice() }
+}
+
+impl<const N: usize> Eq for InlineBytes<N> {}
+
+impl<const N: usize> Hash for InlineBytes<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.as_slice().hash(state); }
+}
+
+impl<const N: usize> fmt::Debug for InlineBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { fmt::Debug::fmt(self.as_slice(), f) }
+}
+
+#[cfg(test)]
+mod inline_bytes_tests {
+    use super::*;
+
+    #[test]
+    fn extend() {
+        let mut buf = InlineBytes::<4>::new();
+        assert!(buf.try_extend_from_slice(b"ab"));
+        assert!(buf.try_extend_from_slice(b"cd"));
+        assert!(!buf.try_extend_from_slice(b"e"));
+        assert_eq!(buf.as_slice(), b"abcd");
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_slice_too_long() { InlineBytes::<2>::from_slice(b"abc"); }
+}
@@ -1,29 +1,97 @@
 #![doc = include_str!("../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "async-graphql")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-graphql")))]
+pub mod async_graphql;
+mod buf;
+#[cfg(feature = "diesel")]
+mod diesel;
+pub mod encoding;
 pub mod error;
+mod format;
+#[cfg(all(feature = "uuid_v7", feature = "std"))]
+mod generator;
+#[cfg(not(feature = "alloc"))]
+mod inline;
 mod oid;
 mod oidstr;
 mod prefix;
+mod range;
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+mod schema;
+#[cfg(feature = "rusqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rusqlite")))]
+pub mod rusqlite;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(any(feature = "surrealdb", feature = "surrealdb2"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "surrealdb", feature = "surrealdb2"))))]
+pub mod surrealdb;
+pub mod timestamp;
+#[cfg(feature = "typeid")]
+mod typeid;
+#[cfg(feature = "utoipa")]
+mod utoipa;
 mod uuid;
 
 pub use crate::{
+    buf::OidBuf,
+    encoding::{OidEncoding, MAX_VALUE_LEN},
     error::{Error, Result},
+    format::{Case, Format, Formatted},
     oid::Oid,
     oidstr::OidStr,
     prefix::Prefix,
+    range::{OidRange, OidStrRange},
+    timestamp::UnixTimestamp,
+    uuid::VALUE_LEN,
 };
 
+#[cfg(all(feature = "uuid_v7", feature = "std"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "uuid_v7", feature = "std"))))]
+pub use crate::generator::{LocalOidGenerator, OidGenerator};
+
+/// Derive macro for [`OidPrefix`] which validates the prefix at compile time
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use typed_oid_derive::OidPrefix;
+
+/// Items used by the code generated by `#[derive(OidPrefix)]`, not public API
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    /// The maximum length of a prefix, which without `alloc` must fit inline
+    #[cfg(feature = "alloc")]
+    pub const MAX_PREFIX_LEN: usize = usize::MAX;
+    #[cfg(not(feature = "alloc"))]
+    pub const MAX_PREFIX_LEN: usize = crate::prefix::INLINE_LEN;
+}
+
 /// Defines the converting a type to a prefix of an OID
 ///
 /// > **NOTE**
 /// > This should be a static representation of the type irrelevant of any
 /// > value in a type instance
+///
+/// With the `derive` feature enabled this trait can be derived with
+/// `#[derive(OidPrefix)]`, in which case the prefix is checked for validity at
+/// compile time.
 pub trait OidPrefix {
     /// Get the static string representation of the prefix.
     ///
     /// The default representation is to use the type name itself.
-    fn prefix() -> &'static str { std::any::type_name::<Self>().split(':').last().unwrap() }
+    #[allow(clippy::double_ended_iterator_last)]
+    fn prefix() -> &'static str { core::any::type_name::<Self>().split(':').last().unwrap() }
 
     /// A partial equality check for the prefix. This is useful in cases when
     /// converting from a string to an Typed-OID where the type and string
@@ -41,5 +109,140 @@ pub trait OidPrefix {
     ///
     /// let oid: Oid<A> = "apple-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
     /// ```
-    fn str_partial_eq(s: &str) -> bool { Self::prefix() == s }
+    fn str_partial_eq(s: &str) -> bool { Self::prefix() == s || Self::aliases().contains(&s) }
+
+    /// Additional prefixes which are accepted when parsing.
+    ///
+    /// These are matched by the default [`OidPrefix::str_partial_eq`] and are
+    /// included in generated schemas, so declaring them here is preferred over
+    /// a custom `str_partial_eq` where possible. The default is no aliases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// struct Usr;
+    /// impl OidPrefix for Usr {
+    ///     fn aliases() -> &'static [&'static str] { &["user"] }
+    /// }
+    ///
+    /// let oid: Oid<Usr> = "user-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+    /// assert_eq!(oid.to_string(), "Usr-4GKFGPRVND4QT3PDR90PDKF66O");
+    /// ```
+    fn aliases() -> &'static [&'static str] { &[] }
+
+    /// Whether the prefix should be matched ignoring ASCII case when parsing,
+    /// i.e. if `true` then `tst-...`, `TST-...` and `Tst-...` are all accepted
+    /// for a prefix of `Tst`.
+    ///
+    /// The default is to match the prefix exactly.
+    fn ignore_prefix_case() -> bool { false }
+
+    /// The [`Case`] used when rendering the value portion of the OID.
+    ///
+    /// The default is [`Case::Upper`], which is the canonical form. Parsing
+    /// always accepts either case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Case, Oid, OidPrefix};
+    /// struct A;
+    /// impl OidPrefix for A {
+    ///     fn prefix() -> &'static str { "a" }
+    ///
+    ///     fn value_case() -> Case { Case::Lower }
+    /// }
+    ///
+    /// let oid: Oid<A> = "a-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+    /// assert_eq!(oid.to_string(), "a-4gkfgprvnd4qt3pdr90pdkf66o");
+    /// ```
+    fn value_case() -> Case { Case::Upper }
+
+    /// The [`OidEncoding`] used for the value portion of the OID.
+    ///
+    /// The default is [`Base32Hex`](encoding::Base32Hex).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{encoding::Base62, Oid, OidEncoding, OidPrefix};
+    /// struct A;
+    /// impl OidPrefix for A {
+    ///     fn encoding() -> &'static dyn OidEncoding { &Base62 }
+    /// }
+    ///
+    /// let oid: Oid<A> = Oid::try_with_uuid("b3cfdafa-3fec-41e2-82bf-ff881131abf1").unwrap();
+    /// assert_eq!(oid.to_string(), "A-5TIcEc7dTWwIb9rnPr7vgf");
+    /// let parsed: Oid<A> = "A-5TIcEc7dTWwIb9rnPr7vgf".parse().unwrap();
+    /// assert_eq!(parsed.uuid(), oid.uuid());
+    /// ```
+    fn encoding() -> &'static dyn OidEncoding { &encoding::Base32Hex }
+
+    /// The separator between the prefix and value portions of the OID.
+    ///
+    /// The default is `-`. The separator must not be a character which can
+    /// appear in the prefix or value, i.e. it can't be ASCII alphanumeric,
+    /// otherwise parsing fails with [`Error::InvalidSeparator`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// struct A;
+    /// impl OidPrefix for A {
+    ///     fn separator() -> char { '_' }
+    /// }
+    ///
+    /// let oid: Oid<A> = "A_4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+    /// assert_eq!(oid.to_string(), "A_4GKFGPRVND4QT3PDR90PDKF66O");
+    /// assert!("A-4GKFGPRVND4QT3PDR90PDKF66O".parse::<Oid<A>>().is_err());
+    /// ```
+    fn separator() -> char { '-' }
+
+    /// An additional separator which is accepted (but never rendered) when
+    /// parsing, e.g. the previous separator while transitioning to a new one.
+    /// Like [`OidPrefix::separator`] it can't be ASCII alphanumeric.
+    ///
+    /// The default is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// struct A;
+    /// impl OidPrefix for A {
+    ///     fn separator() -> char { '_' }
+    ///
+    ///     fn alt_separator() -> Option<char> { Some('-') }
+    /// }
+    ///
+    /// let oid: Oid<A> = "A-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+    /// assert_eq!(oid.to_string(), "A_4GKFGPRVND4QT3PDR90PDKF66O");
+    /// ```
+    fn alt_separator() -> Option<char> { None }
+
+    /// The namespace of the name-based UUIDv5s created by
+    /// [`Oid::new_v5`](crate::Oid::new_v5).
+    ///
+    /// The default is derived from [`OidPrefix::prefix`], so identical names
+    /// under different prefixes never produce the same UUID, and is the same
+    /// namespace [`OidStr::new_v5`] uses for that prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// # use uuid::Uuid;
+    /// struct A;
+    /// impl OidPrefix for A {
+    ///     fn namespace() -> Uuid { Uuid::NAMESPACE_OID }
+    /// }
+    ///
+    /// let oid: Oid<A> = Oid::new_v5(b"1.3.6.1");
+    /// assert_eq!(*oid.uuid(), Uuid::new_v5(&Uuid::NAMESPACE_OID, b"1.3.6.1"));
+    /// ```
+    #[cfg(feature = "uuid_v5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v5")))]
+    fn namespace() -> ::uuid::Uuid { crate::uuid::prefix_namespace(Self::prefix()) }
 }
@@ -1,21 +1,37 @@
-use std::{
+use core::{
+    cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
     marker::PhantomData,
     str::FromStr,
 };
 
-use data_encoding::BASE32HEX_NOPAD;
+#[cfg(all(feature = "uuid_v7", feature = "std"))]
+use uuid::timestamp::context::NoContext;
 #[cfg(feature = "uuid_v7")]
-use uuid::timestamp::{context::NoContext, Timestamp};
+use uuid::timestamp::Timestamp;
 use uuid::Uuid;
 
 use crate::{
+    buf::OidBuf,
+    encoding::{decode_value, OidEncoding, MAX_VALUE_LEN},
     error::{Error, Result},
+    format::{check_separators, split_separator},
     prefix::Prefix,
+    timestamp::{uuid_unix_time, uuid_v7_bounds, UnixTimestamp},
     uuid::uuid_from_str_b32h,
     OidPrefix,
 };
+#[cfg(feature = "uuid_v7")]
+use crate::timestamp::uuid_v7_at;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+#[cfg(feature = "ulid")]
+use crate::encoding::Crockford;
+#[cfg(feature = "typeid")]
+use crate::typeid::{parse_typeid, to_typeid};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, string::String};
 
 /// A Typed Object ID where the Prefix is part of the type
 ///
@@ -40,7 +56,20 @@ use crate::{
 /// // PartialEq or Eq is implemented between these two types.
 /// oid_a == oid_b
 /// ```
-#[derive(PartialEq, Eq)]
+///
+/// TOIDs of the same type are ordered by the bytes of their UUID, which is the
+/// same order as their string representations when using any of the built-in
+/// [`OidEncoding`]s. For UUIDv7 TOIDs this is also the order of creation.
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(
+    feature = "diesel",
+    diesel(sql_type = diesel::sql_types::Uuid),
+    diesel(sql_type = diesel::sql_types::Text),
+    diesel(sql_type = diesel::sql_types::Binary)
+)]
 pub struct Oid<P> {
     uuid: Uuid,
     // Using fn for variance (invariant with respect to P) whereas using *mut would also be
@@ -50,9 +79,9 @@ pub struct Oid<P> {
 
 impl<P> fmt::Debug for Oid<P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct(&format!("Oid<{}>", std::any::type_name::<P>()))
-            .field("uuid", &self.uuid)
-            .finish()
+        // Equivalent to `debug_struct(&format!("Oid<{}>", ...))` without allocating
+        write!(f, "Oid<{}>", core::any::type_name::<P>())?;
+        f.debug_struct("").field("uuid", &self.uuid).finish()
     }
 }
 
@@ -60,6 +89,22 @@ impl<P> fmt::Debug for Oid<P> {
 // https://github.com/rust-lang/rust/issues/26925
 impl<P> Copy for Oid<P> {}
 
+// PartialEq, Eq, PartialOrd and Ord are implemented manually so they don't
+// require the same bounds on `P` (see rust#26925)
+impl<P> PartialEq for Oid<P> {
+    fn eq(&self, other: &Self) -> bool { self.uuid == other.uuid }
+}
+
+impl<P> Eq for Oid<P> {}
+
+impl<P> PartialOrd for Oid<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<P> Ord for Oid<P> {
+    fn cmp(&self, other: &Self) -> Ordering { self.uuid.cmp(&other.uuid) }
+}
+
 impl<P> Clone for Oid<P> {
     fn clone(&self) -> Self { *self }
 }
@@ -70,10 +115,18 @@ impl<P: OidPrefix> Oid<P> {
     #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v4")))]
     pub fn new_v4() -> Self { Self::with_uuid(Uuid::new_v4()) }
 
+    /// Create a new `Oid` with a UUIDv5 (name-based) of `name` in the
+    /// namespace of `P` (see [`OidPrefix::namespace`])
+    ///
+    /// The same name always produces the same `Oid`.
+    #[cfg(feature = "uuid_v5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v5")))]
+    pub fn new_v5(name: &[u8]) -> Self { Self::with_uuid(Uuid::new_v5(&P::namespace(), name)) }
+
     /// Create a new `Oid` with a UUIDv7 (UNIX Epoch based for current system
     /// clock)
-    #[cfg(feature = "uuid_v7")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v7")))]
+    #[cfg(all(feature = "uuid_v7", feature = "std"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "uuid_v7", feature = "std"))))]
     pub fn new_v7_now() -> Self { Self::with_uuid(Uuid::new_v7(Timestamp::now(NoContext))) }
 
     /// Create a new `Oid` with a UUIDv7 (UNIX Epoch based)
@@ -81,6 +134,15 @@ impl<P: OidPrefix> Oid<P> {
     #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v7")))]
     pub fn new_v7(ts: Timestamp) -> Self { Self::with_uuid(Uuid::new_v7(ts)) }
 
+    /// Create a new `Oid` with a UUIDv7 for the given time, e.g. a
+    /// `chrono::DateTime<Utc>` (see [`UnixTimestamp`])
+    ///
+    /// Only millisecond precision is kept, and times before the UNIX epoch
+    /// saturate to the epoch.
+    #[cfg(feature = "uuid_v7")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v7")))]
+    pub fn new_v7_at<T: UnixTimestamp>(t: T) -> Self { Self::with_uuid(uuid_v7_at(&t)) }
+
     /// Create a new Oid with a given UUID
     pub fn with_uuid(uuid: Uuid) -> Self {
         Self {
@@ -99,54 +161,237 @@ impl<P: OidPrefix> Oid<P> {
         Ok(Self::with_uuid(uuid_from_str_b32h(base32_uuid.as_ref())?))
     }
 
+    /// Create a new Oid with a given [ULID](https://github.com/ulid/spec)
+    ///
+    /// The 128 bits of the ULID are used as the UUID unchanged, so the 48 bit
+    /// millisecond timestamp of the ULID occupies the same bits as the
+    /// timestamp of a UUIDv7. Converting a ULID to an Oid and back is lossless,
+    /// and Oids created from ULIDs sort in the same order as the ULIDs. Note
+    /// however the resulting UUID is generally not a valid UUIDv7, since the
+    /// version and variant bits of a ULID are random.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// # use ulid::Ulid;
+    /// struct Tst;
+    /// impl OidPrefix for Tst {}
+    ///
+    /// let ulid: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+    /// let oid = Oid::<Tst>::with_ulid(ulid);
+    /// assert_eq!(oid.uuid().to_string(), "01563e3a-b5d3-d676-4c61-efb99302bd5b");
+    /// assert_eq!(Ulid::from(oid), ulid);
+    /// ```
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn with_ulid(ulid: ::ulid::Ulid) -> Self { Self::with_uuid(Uuid::from_u128(ulid.0)) }
+
+    /// Parse a TOID whose value is a ULID string (i.e. Crockford base32)
+    /// rather than [`OidPrefix::encoding`], e.g.
+    /// `Tst-01ARZ3NDEKTSV4RRFFQ69G5FAV`
+    ///
+    /// To also render the value as a ULID string set [`OidPrefix::encoding`]
+    /// to [`Crockford`](crate::encoding::Crockford), after which the regular
+    /// `FromStr` implementation accepts ULID strings as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// # use ulid::Ulid;
+    /// struct Tst;
+    /// impl OidPrefix for Tst {}
+    ///
+    /// let oid = Oid::<Tst>::parse_ulid("Tst-01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+    /// assert_eq!(oid.to_ulid(), "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap());
+    /// ```
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn parse_ulid(s: &str) -> Result<Self> { parse_with_encoding(s, &Crockford) }
+
+    /// Get the UUID of the TOID as a [ULID](https://github.com/ulid/spec), see
+    /// [`Oid::with_ulid`] for details of the mapping
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn to_ulid(&self) -> ::ulid::Ulid { ::ulid::Ulid(self.uuid.as_u128()) }
+
     /// Get the [`Prefix`] of the TOID
     ///
+    /// See [`Oid::prefix_str`] for a non-panicking alternative.
+    ///
     /// # Panics
     ///
-    /// If the Type `P` translates to an invalid prefix
+    /// If the Type `P` translates to an invalid prefix, or without the `alloc`
+    /// feature a prefix longer than 8 bytes
     pub fn prefix(&self) -> Prefix { Prefix::from_str(P::prefix()).expect("Invalid Prefix") }
 
-    /// Get the value portion of the  of the TOID, which is the base32 encoded
-    /// string following the `-` separator
-    pub fn value(&self) -> String { BASE32HEX_NOPAD.encode(self.uuid.as_bytes()) }
+    /// Get the prefix of the TOID as a string, i.e. [`OidPrefix::prefix`]
+    ///
+    /// Unlike [`Oid::prefix`] this never panics, including for prefixes longer
+    /// than 8 bytes without the `alloc` feature.
+    pub fn prefix_str(&self) -> &'static str { P::prefix() }
+
+    /// Get the value portion of the TOID, which is the UUID encoded with
+    /// [`OidPrefix::encoding`] following the [`OidPrefix::separator`]
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn value(&self) -> String {
+        let mut buf = [0; MAX_VALUE_LEN];
+        self.encode_value(&mut buf).to_owned()
+    }
+
+    /// Encode the value portion of the TOID into `buf` without allocating,
+    /// returning the encoded string
+    ///
+    /// The value is rendered with the [`OidEncoding`](crate::OidEncoding)
+    /// given by [`OidPrefix::encoding`] in the [`Case`](crate::Case) given by
+    /// [`OidPrefix::value_case`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix, MAX_VALUE_LEN};
+    /// struct Tst;
+    /// impl OidPrefix for Tst {}
+    ///
+    /// let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+    /// let mut buf = [0; MAX_VALUE_LEN];
+    /// assert_eq!(oid.encode_value(&mut buf), "0OUS781P4LU7V000PA2A2BN1GC");
+    /// ```
+    pub fn encode_value<'a>(&self, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        P::encoding().encode(&self.uuid, P::value_case(), buf)
+    }
+
+    /// Write the full TOID (`PREFIX-VALUE`, using [`OidPrefix::separator`]) to
+    /// `w` without allocating
+    pub fn write_to<W: fmt::Write>(&self, mut w: W) -> fmt::Result {
+        let mut buf = [0; MAX_VALUE_LEN];
+        w.write_str(P::prefix())?;
+        w.write_char(P::separator())?;
+        w.write_str(self.encode_value(&mut buf))
+    }
+
+    /// Get the full TOID (`PREFIX-VALUE`) as a stack allocated [`OidBuf`]
+    pub fn to_buf(&self) -> OidBuf { OidBuf::build(|w| self.write_to(w)) }
 
     /// Get the UUID of the TOID
     pub fn uuid(&self) -> &Uuid { &self.uuid }
+
+    /// The time the TOID was created at if it has a time-based UUID (version
+    /// 1, 6 or 7), otherwise `None`
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn timestamp(&self) -> Option<SystemTime> { self.created_at() }
+
+    /// The time the TOID was created at as any [`UnixTimestamp`] (e.g. a
+    /// `chrono::DateTime<Utc>`) if it has a time-based UUID (version 1, 6 or
+    /// 7), otherwise `None`
+    pub fn created_at<T: UnixTimestamp>(&self) -> Option<T> {
+        let (secs, nanos) = uuid_unix_time(&self.uuid)?;
+        T::from_unix(secs, nanos)
+    }
+
+    /// The smallest possible `Oid` with a UUIDv7 created within the
+    /// millisecond of the given time, i.e. the lower bound of a time-based
+    /// range scan (see [`OidRange`](crate::OidRange))
+    pub fn min_at<T: UnixTimestamp>(t: T) -> Self { Self::with_uuid(uuid_v7_bounds(&t).0) }
+
+    /// The largest possible `Oid` with a UUIDv7 created within the millisecond
+    /// of the given time, i.e. the upper bound of a time-based range scan (see
+    /// [`OidRange`](crate::OidRange))
+    pub fn max_at<T: UnixTimestamp>(t: T) -> Self { Self::with_uuid(uuid_v7_bounds(&t).1) }
+
+    /// Convert the TOID into a [TypeID](https://github.com/jetify-com/typeid),
+    /// i.e. the lower cased prefix and the UUID in lower case Crockford base32
+    /// separated by `_`
+    ///
+    /// # Errors
+    ///
+    /// If the prefix contains characters other than `A-Z,a-z` or is longer
+    /// than 63 bytes, since it can't be represented as a TypeID prefix
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{Oid, OidPrefix};
+    /// struct User;
+    /// impl OidPrefix for User {}
+    ///
+    /// let oid: Oid<User> = "User-064GKNDCIPRKNF6EMC10J6K0AS".parse().unwrap();
+    /// assert_eq!(oid.to_typeid().unwrap(), "user_01h455vb4pex5vsknk084sn02q");
+    /// ```
+    #[cfg(feature = "typeid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typeid")))]
+    pub fn to_typeid(&self) -> Result<String> { to_typeid(P::prefix(), &self.uuid) }
+
+    /// Parse a [TypeID](https://github.com/jetify-com/typeid) such as
+    /// `user_01h455vb4pex5vsknk084sn02q`
+    ///
+    /// The TypeID prefix is compared with [`OidPrefix::prefix`] ignoring case,
+    /// otherwise it must be accepted by [`OidPrefix::str_partial_eq`].
+    /// TypeIDs which don't follow the specification (e.g. an upper case value)
+    /// are rejected.
+    #[cfg(feature = "typeid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typeid")))]
+    pub fn from_typeid(s: &str) -> Result<Self> {
+        let (pfx, uuid) = parse_typeid(s)?;
+        if !(pfx.eq_ignore_ascii_case(P::prefix()) || P::str_partial_eq(pfx)) {
+            return Err(unexpected_prefix::<P>(pfx));
+        }
+        Ok(Self::with_uuid(uuid))
+    }
 }
 
 impl<P: OidPrefix> fmt::Display for Oid<P> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}-{}", P::prefix(), self.value())
-    }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.write_to(f) }
 }
 
 impl<P: OidPrefix> FromStr for Oid<P> {
     type Err = Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        if let Some((pfx, val)) = s.split_once('-') {
-            if pfx.is_empty() {
-                return Err(Error::MissingPrefix);
-            }
-            if pfx != P::prefix() && !P::str_partial_eq(pfx) {
-                return Err(Error::InvalidPrefix {
-                    valid_until: pfx
-                        .chars()
-                        .zip(P::prefix().chars())
-                        .enumerate()
-                        .find(|(_i, (c1, c2))| c1 != c2)
-                        .map(|(i, _)| i)
-                        .unwrap(),
-                });
-            }
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        parse_with_encoding(s, P::encoding())
+    }
+}
 
-            return Ok(Self {
-                uuid: uuid_from_str_b32h(val)?,
-                _prefix: PhantomData,
-            });
+/// Parses a TOID for `P` whose value is encoded with `encoding`, which isn't
+/// necessarily [`OidPrefix::encoding`]
+fn parse_with_encoding<P: OidPrefix>(s: &str, encoding: &dyn OidEncoding) -> Result<Oid<P>> {
+    check_separators(P::separator(), P::alt_separator())?;
+    if let Some((pfx, val, offset)) = split_separator(s, P::separator(), P::alt_separator()) {
+        if pfx.is_empty() {
+            return Err(Error::MissingPrefix);
         }
+        if !prefix_matches::<P>(pfx) {
+            return Err(unexpected_prefix::<P>(pfx));
+        }
+
+        return Ok(Oid::with_uuid(
+            decode_value(encoding, val).map_err(|e| e.offset(offset))?,
+        ));
+    }
+
+    Err(Error::MissingSeparator)
+}
+
+/// Whether `found` is an acceptable prefix for `P` when parsing
+pub(crate) fn prefix_matches<P: OidPrefix>(found: &str) -> bool {
+    found == P::prefix()
+        || (P::ignore_prefix_case() && found.eq_ignore_ascii_case(P::prefix()))
+        || P::str_partial_eq(found)
+}
 
-        Err(Error::MissingSeparator)
+/// Builds the error for a prefix which does not belong to `P`, which is either
+/// [`Error::InvalidPrefix`] if `found` isn't a valid prefix at all, or
+/// [`Error::UnexpectedPrefix`]
+pub(crate) fn unexpected_prefix<P: OidPrefix>(found: &str) -> Error {
+    match Prefix::from_str(found) {
+        Ok(found) => Error::UnexpectedPrefix {
+            expected: P::prefix(),
+            found,
+        },
+        Err(e) => e,
     }
 }
 
@@ -160,77 +405,198 @@ where
     }
 }
 
+/// Serializes the TOID as a string for human readable formats, and otherwise as
+/// the 16 bytes of the UUID (the prefix is already known from the type)
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl<P: OidPrefix> ::serde::Serialize for Oid<P> {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: ::serde::ser::Serializer,
     {
-        serializer.collect_str(self)
+        if serializer.is_human_readable() {
+            crate::serde::serialize_display(self, serializer)
+        } else {
+            serializer.serialize_bytes(self.uuid.as_bytes())
+        }
     }
 }
 
+/// Deserializes a TOID string for human readable formats, and otherwise either
+/// the 16 bytes of the UUID or a TOID string
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl<'de, P: OidPrefix> ::serde::Deserialize<'de> for Oid<P> {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: ::serde::de::Deserializer<'de>,
     {
-        String::deserialize(deserializer)?
-            .parse()
-            .map_err(::serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(crate::serde::FromStrVisitor::new())
+        } else {
+            deserializer.deserialize_bytes(crate::serde::CompactVisitor::new())
+        }
     }
 }
 
-#[cfg(feature = "surrealdb")]
-#[cfg_attr(docsrs, doc(cfg(feature = "surrealdb")))]
-use surrealdb::sql::Thing;
-
-#[cfg(feature = "surrealdb")]
-#[cfg_attr(docsrs, doc(cfg(feature = "surrealdb")))]
-impl<P: OidPrefix> TryFrom<Thing> for Oid<P> {
-    type Error = crate::Error;
-
-    fn try_from(thing: Thing) -> Result<Self> {
-        if !P::str_partial_eq(&thing.tb) {
-            return Err(Error::InvalidPrefix {
-                valid_until: thing
-                    .tb
-                    .chars()
-                    .zip(P::prefix().chars())
-                    .enumerate()
-                    .find(|(_i, (c1, c2))| c1 != c2)
-                    .map(|(i, _)| i)
-                    .unwrap(),
-            });
-        }
+#[cfg(feature = "serde")]
+impl<P: OidPrefix> crate::serde::FromCompact for Oid<P> {
+    fn from_compact(bytes: &[u8]) -> Option<Result<Self>> {
+        Uuid::from_slice(bytes).ok().map(|uuid| Ok(Self::with_uuid(uuid)))
+    }
+}
 
-        let val = thing.id.to_raw();
-        Self::try_with_uuid(&val).or_else(|_| Self::try_with_uuid_base32(val))
+#[cfg(feature = "serde")]
+impl<P: OidPrefix> crate::serde::structured::Structured for Oid<P> {}
+
+#[cfg(feature = "serde")]
+impl<P: OidPrefix> crate::serde::structured::private::Sealed for Oid<P> {
+    // The prefix is part of the type, so only needs to be checked
+    type Prefix = ();
+
+    fn prefix_str(&self) -> &str { P::prefix() }
+
+    fn uuid(&self) -> &Uuid { &self.uuid }
+
+    fn parse_prefix(prefix: &str) -> Result<()> {
+        if prefix.is_empty() {
+            return Err(Error::MissingPrefix);
+        }
+        if !prefix_matches::<P>(prefix) {
+            return Err(unexpected_prefix::<P>(prefix));
+        }
+        Ok(())
     }
+
+    fn from_parts(_prefix: (), uuid: Uuid) -> Self { Self::with_uuid(uuid) }
+}
+
+#[cfg(feature = "ulid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+impl<P: OidPrefix> From<::ulid::Ulid> for Oid<P> {
+    fn from(ulid: ::ulid::Ulid) -> Self { Self::with_ulid(ulid) }
+}
+
+#[cfg(feature = "ulid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+impl<P: OidPrefix> From<Oid<P>> for ::ulid::Ulid {
+    fn from(oid: Oid<P>) -> Self { oid.to_ulid() }
 }
 
 #[cfg(test)]
 mod oid_tests {
-    #[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+    #[cfg(any(feature = "uuid_v4", all(feature = "uuid_v7", feature = "std")))]
     use wildmatch::WildMatch;
 
-    #[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
     use super::*;
+    use crate::Case;
 
     #[test]
-    #[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+    #[cfg(all(feature = "uuid_v7", feature = "std"))]
+    fn v7_timestamp() {
+        use std::time::{Duration, SystemTime};
+
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        // Only millisecond precision is kept
+        let at = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let oid: Oid<Tst> = Oid::new_v7_at(at);
+        assert_eq!(
+            oid.timestamp(),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+        );
+        assert_eq!(oid.created_at::<SystemTime>(), oid.timestamp());
+    }
+
+    #[test]
+    #[cfg(all(feature = "uuid_v7", feature = "chrono"))]
+    fn v7_created_at_chrono() {
+        use chrono::{DateTime, Utc};
+
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let at = DateTime::<Utc>::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let oid: Oid<Tst> = Oid::new_v7_at(at);
+        assert_eq!(oid.created_at(), Some(at));
+    }
+
+    #[test]
+    #[cfg(feature = "uuid_v5")]
+    fn v5() {
+        use crate::OidStr;
+
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        struct Frm;
+        impl OidPrefix for Frm {}
+
+        struct Ns;
+        impl OidPrefix for Ns {
+            fn namespace() -> Uuid { Uuid::NAMESPACE_OID }
+        }
+
+        let oid: Oid<Tst> = Oid::new_v5(b"order-1");
+        assert_eq!(oid, Oid::new_v5(b"order-1"));
+        assert_eq!(oid.uuid().to_string(), "f9cd37bb-ffd4-50d1-bf7d-91c0184ec0dc");
+        assert_ne!(oid.uuid(), Oid::<Frm>::new_v5(b"order-1").uuid());
+        assert_ne!(oid, Oid::new_v5(b"order-2"));
+        assert_eq!(
+            *Oid::<Ns>::new_v5(b"order-1").uuid(),
+            Uuid::new_v5(&Uuid::NAMESPACE_OID, b"order-1")
+        );
+
+        let oidstr = OidStr::new_v5("Tst", b"order-1").unwrap();
+        assert_eq!(oidstr.uuid(), oid.uuid());
+        assert!(OidStr::new_v5("Tst-", b"order-1").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "uuid_v4", feature = "std"))]
+    fn v4_timestamp() {
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        assert_eq!(Oid::<Tst>::new_v4().timestamp(), None);
+    }
+
+    #[test]
+    fn ord() {
+        // No bounds are required on the prefix type
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let mut map = std::collections::BTreeMap::new();
+        for uuid in [
+            "063dc3a0-3925-7c7f-8000-ca84a12ee183",
+            "00000000-0000-0000-0000-000000000000",
+            "ffffffff-ffff-ffff-ffff-ffffffffffff",
+        ] {
+            let oid: Oid<Tst> = Oid::try_with_uuid(uuid).unwrap();
+            map.insert(oid, oid.to_string());
+        }
+        let values: Vec<_> = map.values().cloned().collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(values, sorted);
+    }
+
+    #[test]
+    #[cfg(any(feature = "uuid_v4", all(feature = "uuid_v7", feature = "std")))]
     fn typed_oid() {
         #[derive(Debug)]
         struct Tst;
         impl OidPrefix for Tst {}
 
-        #[cfg_attr(all(feature = "uuid_v4", feature = "uuid_v7"), allow(unused_variables))]
+        #[cfg_attr(
+            all(feature = "uuid_v4", feature = "uuid_v7", feature = "std"),
+            allow(unused_variables)
+        )]
         #[cfg(feature = "uuid_v4")]
         let oid: Oid<Tst> = Oid::new_v4();
-        #[cfg(feature = "uuid_v7")]
+        #[cfg(all(feature = "uuid_v7", feature = "std"))]
         let oid: Oid<Tst> = Oid::new_v7_now();
         assert!(
             WildMatch::new("Tst-??????????????????????????").matches(&oid.to_string()),
@@ -249,11 +615,17 @@ mod oid_tests {
 
         let res = "Frm-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>();
         assert!(res.is_err());
-        assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 0 });
+        assert_eq!(
+            res.unwrap_err(),
+            Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Frm".parse().unwrap()
+            }
+        );
     }
 
     #[test]
-    #[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+    #[cfg(any(feature = "uuid_v4", all(feature = "uuid_v7", feature = "std")))]
     fn from_uuid_str() {
         #[derive(Debug)]
         struct Tst;
@@ -292,16 +664,29 @@ mod oid_tests {
     }
 
     #[test]
-    #[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+    fn long_prefix_str() {
+        #[derive(Debug)]
+        struct TestingTesting;
+        impl OidPrefix for TestingTesting {}
+
+        let oid: Oid<TestingTesting> = Oid::with_uuid(Uuid::nil());
+        assert_eq!(oid.prefix_str(), "TestingTesting");
+    }
+
+    #[test]
+    #[cfg(any(feature = "uuid_v4", all(feature = "uuid_v7", feature = "std")))]
     fn long_typed_oid() {
         #[derive(Debug)]
         struct TestingTesting;
         impl OidPrefix for TestingTesting {}
 
-        #[cfg_attr(all(feature = "uuid_v4", feature = "uuid_v7"), allow(unused_variables))]
+        #[cfg_attr(
+            all(feature = "uuid_v4", feature = "uuid_v7", feature = "std"),
+            allow(unused_variables)
+        )]
         #[cfg(feature = "uuid_v4")]
         let oid: Oid<TestingTesting> = Oid::new_v4();
-        #[cfg(feature = "uuid_v7")]
+        #[cfg(all(feature = "uuid_v7", feature = "std"))]
         let oid: Oid<TestingTesting> = Oid::new_v7_now();
         assert!(
             WildMatch::new("TestingTesting-??????????????????????????").matches(&oid.to_string()),
@@ -320,48 +705,367 @@ mod oid_tests {
 
         let res = "Frm-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<TestingTesting>>();
         assert!(res.is_err());
-        assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 0 });
+        assert_eq!(
+            res.unwrap_err(),
+            Error::UnexpectedPrefix {
+                expected: "TestingTesting",
+                found: "Frm".parse().unwrap()
+            }
+        );
     }
-}
 
-#[cfg(test)]
-#[cfg(feature = "surrealdb")]
-mod surreal_thing_oid_tests {
-    use surrealdb::sql::Id;
+    #[test]
+    fn to_buf() {
+        #[derive(Debug)]
+        struct Tst;
+        impl OidPrefix for Tst {}
 
-    use super::*;
+        let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(oid.to_buf(), "Tst-0OUS781P4LU7V000PA2A2BN1GC");
+        assert_eq!(&*oid.to_buf(), oid.to_string());
+
+        let mut s = String::new();
+        oid.write_to(&mut s).unwrap();
+        assert_eq!(s, "Tst-0OUS781P4LU7V000PA2A2BN1GC");
+    }
 
     #[test]
-    fn uuid() {
-        #[derive(Debug)]
+    fn lowercase_value() {
+        #[derive(Debug, PartialEq)]
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let upper: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        let lower: Oid<Tst> = "Tst-0ous781p4lu7v000pa2a2bn1gc".parse().unwrap();
+        assert_eq!(upper, lower);
+        assert_eq!(lower.to_string(), "Tst-0OUS781P4LU7V000PA2A2BN1GC");
+    }
+
+    #[test]
+    fn value_case_lower() {
+        #[derive(Debug, PartialEq)]
+        struct Tst;
+        impl OidPrefix for Tst {
+            fn value_case() -> Case { Case::Lower }
+        }
+
+        let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(oid.to_string(), "Tst-0ous781p4lu7v000pa2a2bn1gc");
+        #[cfg(feature = "alloc")]
+        assert_eq!(oid.value(), "0ous781p4lu7v000pa2a2bn1gc");
+        assert_eq!(oid.to_buf(), "Tst-0ous781p4lu7v000pa2a2bn1gc");
+        assert_eq!(oid.to_string().parse::<Oid<Tst>>(), Ok(oid));
+    }
+
+    #[test]
+    fn separator() {
+        #[derive(Debug, PartialEq)]
         struct Tst;
         impl OidPrefix for Tst {
-            fn str_partial_eq(s: &str) -> bool { "test" == s }
+            fn separator() -> char { '_' }
         }
 
-        let thing = Thing {
-            tb: "test".to_string(),
-            id: Id::String("063dc3a0-3925-7c7f-8000-ca84a12ee183".to_string()),
-        };
+        let oid: Oid<Tst> = "Tst_0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(oid.to_string(), "Tst_0OUS781P4LU7V000PA2A2BN1GC");
+        assert_eq!(oid.to_buf(), "Tst_0OUS781P4LU7V000PA2A2BN1GC");
+        assert_eq!(
+            "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>(),
+            Err(Error::MissingSeparator)
+        );
+    }
+
+    #[test]
+    fn alt_separator() {
+        #[derive(Debug, PartialEq)]
+        struct Tst;
+        impl OidPrefix for Tst {
+            fn separator() -> char { '_' }
 
-        let toid: Result<Oid<Tst>> = thing.try_into();
-        assert!(toid.is_ok());
+            fn alt_separator() -> Option<char> { Some('-') }
+        }
+
+        let new: Oid<Tst> = "Tst_0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        let old: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(new, old);
+        assert_eq!(old.to_string(), "Tst_0OUS781P4LU7V000PA2A2BN1GC");
     }
 
     #[test]
-    fn uuid_base32() {
-        #[derive(Debug)]
+    fn invalid_separator() {
+        #[derive(Debug, PartialEq)]
         struct Tst;
         impl OidPrefix for Tst {
-            fn str_partial_eq(s: &str) -> bool { "test" == s }
+            fn separator() -> char { 'S' }
         }
 
-        let thing = Thing {
-            tb: "test".to_string(),
-            id: Id::String("0OUS781P4LU7V000PA2A2BN1GC".to_string()),
-        };
+        #[derive(Debug, PartialEq)]
+        struct Alt;
+        impl OidPrefix for Alt {
+            fn alt_separator() -> Option<char> { Some('0') }
+        }
+
+        assert_eq!(
+            "TstS0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>(),
+            Err(Error::InvalidSeparator { separator: 'S' })
+        );
+        assert_eq!(
+            "Alt-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Alt>>(),
+            Err(Error::InvalidSeparator { separator: '0' })
+        );
+    }
 
-        let toid: Result<Oid<Tst>> = thing.try_into();
-        assert!(toid.is_ok());
+    #[test]
+    fn ignore_prefix_case() {
+        #[derive(Debug, PartialEq)]
+        struct Tst;
+        impl OidPrefix for Tst {
+            fn ignore_prefix_case() -> bool { true }
+        }
+
+        for s in [
+            "Tst-0OUS781P4LU7V000PA2A2BN1GC",
+            "tst-0OUS781P4LU7V000PA2A2BN1GC",
+            "TST-0ous781p4lu7v000pa2a2bn1gc",
+        ] {
+            let oid: Oid<Tst> = s.parse().unwrap();
+            assert_eq!(oid.to_string(), "Tst-0OUS781P4LU7V000PA2A2BN1GC");
+        }
+
+        #[derive(Debug)]
+        struct Exact;
+        impl OidPrefix for Exact {
+            fn prefix() -> &'static str { "Tst" }
+        }
+        assert!("tst-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Exact>>().is_err());
+    }
+
+    #[test]
+    fn encoding() {
+        use crate::encoding::{Base58, Crockford, Hex};
+
+        #[derive(Debug, PartialEq)]
+        struct Ulid;
+        impl OidPrefix for Ulid {
+            fn encoding() -> &'static dyn crate::OidEncoding { &Crockford }
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct B58;
+        impl OidPrefix for B58 {
+            fn encoding() -> &'static dyn crate::OidEncoding { &Base58 }
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Hx;
+        impl OidPrefix for Hx {
+            fn encoding() -> &'static dyn crate::OidEncoding { &Hex }
+
+            fn value_case() -> Case { Case::Lower }
+        }
+
+        let uuid: Uuid = "063dc3a0-3925-7c7f-8000-ca84a12ee183".parse().unwrap();
+
+        let oid: Oid<Ulid> = Oid::with_uuid(uuid);
+        assert_eq!(oid.to_string(), "Ulid-067Q1T0E95FHZR006AGJGJXRC3");
+        assert_eq!(oid.to_string().parse::<Oid<Ulid>>(), Ok(oid));
+
+        let oid: Oid<B58> = Oid::with_uuid(uuid);
+        assert_eq!(oid.to_string().len(), 4 + 22);
+        assert_eq!(oid.to_string().parse::<Oid<B58>>(), Ok(oid));
+
+        let oid: Oid<Hx> = Oid::with_uuid(uuid);
+        assert_eq!(oid.to_string(), "Hx-063dc3a039257c7f8000ca84a12ee183");
+        assert_eq!(oid.to_string().parse::<Oid<Hx>>(), Ok(oid));
+
+        // The base32hex value is not a valid hex value
+        assert_eq!(
+            "Hx-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Hx>>(),
+            Err(Error::WrongValueLength {
+                expected: 32,
+                found: 26
+            })
+        );
+    }
+
+    #[test]
+    fn prefix_mismatch_shorter() {
+        #[derive(Debug)]
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let res = "Ts-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>();
+        assert_eq!(
+            res.unwrap_err(),
+            Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Ts".parse().unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn prefix_mismatch_longer() {
+        #[derive(Debug)]
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let res = "Tstx-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>();
+        assert_eq!(
+            res.unwrap_err(),
+            Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Tstx".parse().unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn prefix_invalid_char() {
+        #[derive(Debug)]
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let res = "Ts_t-0OUS781P4LU7V000PA2A2BN1GC".parse::<Oid<Tst>>();
+        assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 2 });
+    }
+
+    #[test]
+    fn value_errors_offset_from_input_start() {
+        #[derive(Debug)]
+        struct Tst;
+        impl OidPrefix for Tst {}
+
+        let res = "Tst-0OUS781P4LU7V000PA2A2B&1GC".parse::<Oid<Tst>>();
+        assert_eq!(
+            res.unwrap_err(),
+            Error::InvalidValueChar {
+                index: 26,
+                char: '&'
+            }
+        );
+
+        let res = "Tst-0OUS781P4LU7V000PA2A2BN1G".parse::<Oid<Tst>>();
+        assert_eq!(
+            res.unwrap_err(),
+            Error::WrongValueLength {
+                expected: 26,
+                found: 25
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod oid_proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "\\PC*") {
+            let _ = s.parse::<Oid<Tst>>();
+        }
+
+        #[test]
+        fn from_str_toid_shaped_never_panics(s in "[A-Za-z0-9_]{0,8}-[0-9A-Za-z&-]{0,30}") {
+            let _ = s.parse::<Oid<Tst>>();
+        }
+
+        #[test]
+        fn from_str_roundtrip(bytes in any::<[u8; 16]>()) {
+            let oid: Oid<Tst> = Oid::with_uuid(Uuid::from_bytes(bytes));
+            prop_assert_eq!(oid.to_string().parse::<Oid<Tst>>(), Ok(oid));
+        }
+
+        #[test]
+        fn ord_matches_display(a in any::<[u8; 16]>(), b in any::<[u8; 16]>()) {
+            let a: Oid<Tst> = Oid::with_uuid(Uuid::from_bytes(a));
+            let b: Oid<Tst> = Oid::with_uuid(Uuid::from_bytes(b));
+            prop_assert_eq!(a.cmp(&b), a.to_string().cmp(&b.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "ulid", feature = "alloc"))]
+mod ulid_oid_tests {
+    use proptest::prelude::*;
+    use ::ulid::Ulid;
+
+    use super::*;
+    use crate::encoding::Crockford;
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[derive(Debug, PartialEq)]
+    struct Ulids;
+    impl OidPrefix for Ulids {
+        fn encoding() -> &'static dyn OidEncoding { &Crockford }
+    }
+
+    #[test]
+    fn from_into() {
+        let ulid: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+        let oid: Oid<Tst> = ulid.into();
+        assert_eq!(oid.uuid().as_bytes(), &ulid.to_bytes());
+        assert_eq!(Ulid::from(oid), ulid);
+    }
+
+    #[test]
+    fn parse_ulid() {
+        let oid = Oid::<Tst>::parse_ulid("Tst-01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+        assert_eq!(oid.to_ulid().to_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(
+            Oid::<Tst>::parse_ulid("Tst-01arz3ndektsv4rrffq69g5fav"),
+            Ok(oid)
+        );
+        assert_eq!(
+            Oid::<Tst>::parse_ulid("Tst-81ARZ3NDEKTSV4RRFFQ69G5FAV"),
+            Err(Error::InvalidValueChar {
+                index: 4,
+                char: '8'
+            })
+        );
+        assert_eq!(
+            Oid::<Tst>::parse_ulid("Oth-01ARZ3NDEKTSV4RRFFQ69G5FAV"),
+            Err(Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Oth".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uuid_v7")]
+    fn v7_timestamp() {
+        let ts = Timestamp::from_unix(uuid::NoContext, 1_700_000_000, 123_000_000);
+        let oid = Oid::<Tst>::new_v7(ts);
+        assert_eq!(oid.to_ulid().timestamp_ms(), 1_700_000_000_123);
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip(n in any::<u128>()) {
+            let ulid = Ulid(n);
+            let oid = Oid::<Ulids>::with_ulid(ulid);
+            prop_assert_eq!(oid.to_ulid(), ulid);
+            prop_assert_eq!(oid.value(), ulid.to_string());
+            prop_assert_eq!(oid.to_string().parse::<Oid<Ulids>>(), Ok(oid));
+        }
+
+        #[test]
+        fn preserves_order(a in any::<u128>(), b in any::<u128>()) {
+            let (a, b) = (Ulid(a), Ulid(b));
+            let (oid_a, oid_b) = (Oid::<Tst>::with_ulid(a), Oid::<Tst>::with_ulid(b));
+            prop_assert_eq!(a.cmp(&b), oid_a.uuid().cmp(oid_b.uuid()));
+            prop_assert_eq!(a.cmp(&b), oid_a.value().cmp(&oid_b.value()));
+        }
     }
 }
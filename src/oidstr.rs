@@ -1,22 +1,48 @@
-use std::{
+use core::{
     fmt,
     hash::{Hash, Hasher},
     str::FromStr,
 };
 
-use data_encoding::BASE32HEX_NOPAD;
+#[cfg(all(feature = "uuid_v7", feature = "std"))]
+use uuid::timestamp::context::NoContext;
 #[cfg(feature = "uuid_v7")]
-use uuid::timestamp::{context::NoContext, Timestamp};
+use uuid::timestamp::Timestamp;
 use uuid::Uuid;
 
 use crate::{
+    buf::OidBuf,
+    encoding::{decode_value, MAX_VALUE_LEN},
     error::{Error, Result},
+    format::{split_separator, Format, Formatted},
     prefix::Prefix,
+    timestamp::{uuid_unix_time, uuid_v7_bounds, UnixTimestamp},
     uuid::uuid_from_str_b32h,
 };
+#[cfg(feature = "uuid_v7")]
+use crate::timestamp::uuid_v7_at;
+#[cfg(feature = "uuid_v5")]
+use crate::uuid::prefix_namespace;
+#[cfg(feature = "serde")]
+use crate::prefix::valid_prefix_char;
+#[cfg(feature = "typeid")]
+use crate::typeid::{parse_typeid, to_typeid};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, string::String};
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
 /// An Object ID
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// OIDs are ordered by their [`Prefix`] and then by the bytes of their UUID,
+/// which is the same order as their string representations in the default
+/// [`Format`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub struct OidStr {
     prefix: Prefix,
     uuid: Uuid,
@@ -39,10 +65,29 @@ impl OidStr {
         })
     }
 
+    /// Create a new OID with a given [`Prefix`] and a UUIDv5 (name-based) of
+    /// `name` in the default namespace of the prefix
+    ///
+    /// The same prefix and name always produce the same OID, which matches
+    /// [`Oid::new_v5`](crate::Oid::new_v5) for a type with that prefix unless
+    /// it overrides [`OidPrefix::namespace`](crate::OidPrefix::namespace).
+    #[cfg(feature = "uuid_v5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v5")))]
+    pub fn new_v5<P>(prefix: P, name: &[u8]) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+    {
+        let prefix = prefix.try_into()?;
+        Ok(Self {
+            uuid: Uuid::new_v5(&prefix_namespace(prefix.as_str()), name),
+            prefix,
+        })
+    }
+
     /// Create a new OID with a given [`Prefix`] and generating a new UUIDv7
     /// (UNIX Epoch based on current system clock)
-    #[cfg(feature = "uuid_v7")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v7")))]
+    #[cfg(all(feature = "uuid_v7", feature = "std"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "uuid_v7", feature = "std"))))]
     pub fn new_v7_now<P>(prefix: P) -> Result<Self>
     where
         P: TryInto<Prefix, Error = Error>,
@@ -64,6 +109,21 @@ impl OidStr {
         Self::with_uuid(prefix, Uuid::new_v7(ts))
     }
 
+    /// Create a new OID with a given [`Prefix`] and a UUIDv7 for the given
+    /// time, e.g. a `chrono::DateTime<Utc>` (see [`UnixTimestamp`])
+    ///
+    /// Only millisecond precision is kept, and times before the UNIX epoch
+    /// saturate to the epoch.
+    #[cfg(feature = "uuid_v7")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "uuid_v7")))]
+    pub fn new_v7_at<P, T>(prefix: P, t: T) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+        T: UnixTimestamp,
+    {
+        Self::with_uuid(prefix, uuid_v7_at(&t))
+    }
+
     /// Create a new OID with a given [`Prefix`] and a given UUID.
     ///
     /// > **NOTE:** The Prefix must be ASCII characters of `A-Z,a-z,0-9` (this
@@ -99,40 +159,220 @@ impl OidStr {
         Self::with_uuid(prefix, uuid_from_str_b32h(base32_uuid.as_ref())?)
     }
 
+    /// Create a new OID with a given [`Prefix`] and a given
+    /// [ULID](https://github.com/ulid/spec)
+    ///
+    /// See [`Oid::with_ulid`](crate::Oid::with_ulid) for details of how ULIDs
+    /// map to UUIDs.
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn with_ulid<P>(prefix: P, ulid: ::ulid::Ulid) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+    {
+        Self::with_uuid(prefix, Uuid::from_u128(ulid.0))
+    }
+
     /// Get the [`Prefix`] of the OID
     pub fn prefix(&self) -> &Prefix { &self.prefix }
 
-    /// Get the value portion of the  of the OID, which is the base32 encoded
-    /// string following the `-` separator
-    pub fn value(&self) -> String { BASE32HEX_NOPAD.encode(self.uuid.as_bytes()) }
+    /// Get the value portion of the OID, which is the UUID encoded with the
+    /// default [`Format`] (i.e. base32hex) following the separator
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn value(&self) -> String {
+        let mut buf = [0; MAX_VALUE_LEN];
+        self.encode_value(&mut buf).to_owned()
+    }
 
-    /// Get the UUID of the OID
-    pub fn uuid(&self) -> &Uuid { &self.uuid }
-}
+    /// Encode the value portion of the OID into `buf` without allocating,
+    /// returning the encoded string
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{OidStr, MAX_VALUE_LEN};
+    /// let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+    /// let mut buf = [0; MAX_VALUE_LEN];
+    /// assert_eq!(oid.encode_value(&mut buf), "0OQPKOAADLRUJ000J7U2UGNS2G");
+    /// ```
+    pub fn encode_value<'a>(&self, buf: &'a mut [u8; MAX_VALUE_LEN]) -> &'a str {
+        self.encode_value_with(buf, Format::new())
+    }
 
-impl FromStr for OidStr {
-    type Err = Error;
+    /// Encode the value portion of the OID into `buf` using a given [`Format`]
+    /// without allocating, returning the encoded string
+    pub fn encode_value_with<'a>(&self, buf: &'a mut [u8; MAX_VALUE_LEN], format: Format) -> &'a str {
+        format.encoding.encode(&self.uuid, format.value_case, buf)
+    }
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        if let Some((pfx, val)) = s.split_once('-') {
+    /// Write the full OID (`PREFIX-VALUE`) to `w` without allocating
+    pub fn write_to<W: fmt::Write>(&self, w: W) -> fmt::Result {
+        self.write_to_with(w, Format::new())
+    }
+
+    /// Write the full OID (`PREFIX-VALUE`) to `w` using a given [`Format`]
+    /// without allocating
+    pub fn write_to_with<W: fmt::Write>(&self, mut w: W, format: Format) -> fmt::Result {
+        let mut buf = [0; MAX_VALUE_LEN];
+        w.write_str(self.prefix.as_str())?;
+        w.write_char(format.separator)?;
+        w.write_str(self.encode_value_with(&mut buf, format))
+    }
+
+    /// Get the full OID (`PREFIX-VALUE`) as a stack allocated [`OidBuf`]
+    pub fn to_buf(&self) -> OidBuf { OidBuf::build(|w| self.write_to(w)) }
+
+    /// Get the full OID (`PREFIX-VALUE`) rendered with a given [`Format`] as a
+    /// stack allocated [`OidBuf`]
+    pub fn to_buf_with(&self, format: Format) -> OidBuf {
+        OidBuf::build(|w| self.write_to_with(w, format))
+    }
+
+    /// Render the OID using a given [`Format`] rather than the canonical one
+    /// used by the `Display` implementation
+    pub fn display_with(&self, format: Format) -> Formatted<'_> {
+        Formatted { oid: self, format }
+    }
+
+    /// Parse an OID which was rendered with a given [`Format`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::{encoding::Hex, Format, OidStr};
+    /// let hex = Format::new().encoding(&Hex);
+    /// let oid = OidStr::parse_with("TST-063DC3A039257C7F8000CA84A12EE183", hex).unwrap();
+    /// assert_eq!(oid.to_string(), "TST-0OUS781P4LU7V000PA2A2BN1GC");
+    /// ```
+    pub fn parse_with(s: &str, format: Format) -> Result<Self> {
+        let split = split_separator(s, format.separator, format.alt_separator);
+        if let Some((pfx, val, offset)) = split {
             if pfx.is_empty() {
                 return Err(Error::MissingPrefix);
             }
 
             return Ok(Self {
                 prefix: pfx.parse()?,
-                uuid: uuid_from_str_b32h(val)?,
+                uuid: decode_value(format.encoding, val).map_err(|e| e.offset(offset))?,
             });
         }
 
         Err(Error::MissingSeparator)
     }
+
+    /// Parse an OID whose value is a ULID string (i.e. Crockford base32), e.g.
+    /// `TST-01ARZ3NDEKTSV4RRFFQ69G5FAV`
+    ///
+    /// This is equivalent to [`OidStr::parse_with`] using the
+    /// [`Crockford`](crate::encoding::Crockford) encoding, which can also be
+    /// used to render the value as a ULID string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::OidStr;
+    /// let oid = OidStr::parse_ulid("TST-01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+    /// assert_eq!(oid.to_ulid(), "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap());
+    /// assert_eq!(oid.to_string(), "TST-05B3SELLQFB7CJ31TUSP60LTBC");
+    /// ```
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn parse_ulid(s: &str) -> Result<Self> {
+        Self::parse_with(s, Format::new().encoding(&crate::encoding::Crockford))
+    }
+
+    /// Get the UUID of the OID
+    pub fn uuid(&self) -> &Uuid { &self.uuid }
+
+    /// The time the OID was created at if it has a time-based UUID (version 1,
+    /// 6 or 7), otherwise `None`
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn timestamp(&self) -> Option<SystemTime> { self.created_at() }
+
+    /// The time the OID was created at as any [`UnixTimestamp`] (e.g. a
+    /// `chrono::DateTime<Utc>`) if it has a time-based UUID (version 1, 6 or
+    /// 7), otherwise `None`
+    pub fn created_at<T: UnixTimestamp>(&self) -> Option<T> {
+        let (secs, nanos) = uuid_unix_time(&self.uuid)?;
+        T::from_unix(secs, nanos)
+    }
+
+    /// The smallest possible OID with a given [`Prefix`] and a UUIDv7 created
+    /// within the millisecond of the given time, i.e. the lower bound of a
+    /// time-based range scan (see [`OidStrRange`](crate::OidStrRange))
+    pub fn min_at<P, T>(prefix: P, t: T) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+        T: UnixTimestamp,
+    {
+        Self::with_uuid(prefix, uuid_v7_bounds(&t).0)
+    }
+
+    /// The largest possible OID with a given [`Prefix`] and a UUIDv7 created
+    /// within the millisecond of the given time, i.e. the upper bound of a
+    /// time-based range scan (see [`OidStrRange`](crate::OidStrRange))
+    pub fn max_at<P, T>(prefix: P, t: T) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+        T: UnixTimestamp,
+    {
+        Self::with_uuid(prefix, uuid_v7_bounds(&t).1)
+    }
+
+    /// Get the UUID of the OID as a [ULID](https://github.com/ulid/spec)
+    #[cfg(feature = "ulid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+    pub fn to_ulid(&self) -> ::ulid::Ulid { ::ulid::Ulid(self.uuid.as_u128()) }
+
+    /// Convert the OID into a [TypeID](https://github.com/jetify-com/typeid),
+    /// i.e. the lower cased prefix and the UUID in lower case Crockford base32
+    /// separated by `_`
+    ///
+    /// # Errors
+    ///
+    /// If the prefix contains characters other than `A-Z,a-z` or is longer
+    /// than 63 bytes, since it can't be represented as a TypeID prefix
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use typed_oid::OidStr;
+    /// let oid: OidStr = "user-064GKNDCIPRKNF6EMC10J6K0AS".parse().unwrap();
+    /// let typeid = oid.to_typeid().unwrap();
+    /// assert_eq!(typeid, "user_01h455vb4pex5vsknk084sn02q");
+    /// assert_eq!(OidStr::from_typeid(&typeid).unwrap(), oid);
+    /// ```
+    #[cfg(feature = "typeid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typeid")))]
+    pub fn to_typeid(&self) -> Result<String> { to_typeid(self.prefix.as_str(), &self.uuid) }
+
+    /// Parse a [TypeID](https://github.com/jetify-com/typeid) such as
+    /// `user_01h455vb4pex5vsknk084sn02q`
+    ///
+    /// TypeIDs which don't follow the specification (e.g. an upper case value)
+    /// are rejected, as are TypeIDs with an empty prefix or a prefix
+    /// containing `_` since those can't be represented by a [`Prefix`].
+    #[cfg(feature = "typeid")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typeid")))]
+    pub fn from_typeid(s: &str) -> Result<Self> {
+        let (pfx, uuid) = parse_typeid(s)?;
+        Ok(Self {
+            prefix: pfx.parse()?,
+            uuid,
+        })
+    }
+}
+
+impl FromStr for OidStr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> { Self::parse_with(s, Format::new()) }
 }
 
 impl fmt::Display for OidStr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}-{}", self.prefix, self.value())
-    }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.write_to(f) }
 }
 
 impl Hash for OidStr {
@@ -142,36 +382,105 @@ impl Hash for OidStr {
     }
 }
 
+/// Serializes the TOID as a string for human readable formats, and otherwise as
+/// the prefix bytes followed by the 16 bytes of the UUID
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl ::serde::Serialize for OidStr {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: ::serde::ser::Serializer,
     {
-        serializer.collect_str(self)
+        if serializer.is_human_readable() {
+            crate::serde::serialize_display(self, serializer)
+        } else {
+            crate::serde::serialize_prefixed(&self.prefix, &self.uuid, serializer)
+        }
     }
 }
 
+/// Deserializes a TOID string for human readable formats, and otherwise either
+/// the prefix bytes followed by the 16 bytes of the UUID or a TOID string
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl<'de> ::serde::Deserialize<'de> for OidStr {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: ::serde::de::Deserializer<'de>,
     {
-        String::deserialize(deserializer)?
-            .parse()
-            .map_err(::serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(crate::serde::FromStrVisitor::new())
+        } else {
+            deserializer.deserialize_bytes(crate::serde::CompactVisitor::new())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl crate::serde::FromCompact for OidStr {
+    fn from_compact(bytes: &[u8]) -> Option<Result<Self>> {
+        let (prefix, uuid) = bytes.split_at(bytes.len().checked_sub(16)?);
+        // A TOID string always has its separator within the would-be prefix, as
+        // every encoded value is longer than 16 bytes
+        if prefix.is_empty() || !prefix.iter().all(|&c| valid_prefix_char(c)) {
+            return None;
+        }
+        let uuid = Uuid::from_slice(uuid).ok()?;
+        Some(Prefix::from_slice(prefix).map(|prefix| Self { prefix, uuid }))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl crate::serde::structured::Structured for OidStr {}
+
+#[cfg(feature = "serde")]
+impl crate::serde::structured::private::Sealed for OidStr {
+    type Prefix = Prefix;
+
+    fn prefix_str(&self) -> &str { self.prefix.as_str() }
+
+    fn uuid(&self) -> &Uuid { &self.uuid }
+
+    fn parse_prefix(prefix: &str) -> Result<Prefix> {
+        if prefix.is_empty() {
+            return Err(Error::MissingPrefix);
+        }
+        prefix.parse()
     }
+
+    fn from_parts(prefix: Prefix, uuid: Uuid) -> Self { Self { prefix, uuid } }
+}
+
+#[cfg(feature = "ulid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+impl From<OidStr> for ::ulid::Ulid {
+    fn from(oid: OidStr) -> Self { oid.to_ulid() }
+}
+
+#[cfg(feature = "ulid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ulid")))]
+impl From<&OidStr> for ::ulid::Ulid {
+    fn from(oid: &OidStr) -> Self { oid.to_ulid() }
 }
 
 #[cfg(test)]
-#[cfg(any(feature = "uuid_v4", feature = "uuid_v7"))]
+#[cfg(any(feature = "uuid_v4", all(feature = "uuid_v7", feature = "std")))]
 mod oid_tests {
     use wildmatch::WildMatch;
 
     use super::*;
+    use crate::Case;
+
+    #[test]
+    #[cfg(all(feature = "uuid_v7", feature = "std"))]
+    fn v7_timestamp() {
+        use std::time::{Duration, SystemTime};
+
+        let at = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let oid = OidStr::new_v7_at("Tst", at).unwrap();
+        assert_eq!(oid.timestamp(), Some(at));
+        assert!(OidStr::new_v7_at("Tst-", at).is_err());
+    }
 
     #[test]
     #[cfg(feature = "uuid_v4")]
@@ -182,7 +491,7 @@ mod oid_tests {
     }
 
     #[test]
-    #[cfg(feature = "uuid_v7")]
+    #[cfg(all(feature = "uuid_v7", feature = "std"))]
     fn oid_to_str_v7() -> Result<()> {
         let oid = OidStr::new_v7_now("TST")?;
         assert!(WildMatch::new("TST-??????????????????????????").matches(&oid.to_string()));
@@ -202,6 +511,7 @@ mod oid_tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn str_to_oid_long() {
         let res = "TestingTesting-0OQPKOAADLRUJ000J7U2UGNS2G".parse::<OidStr>();
         assert_eq!(
@@ -231,7 +541,19 @@ mod oid_tests {
     fn str_to_oid_err_decode() {
         let res = "TST-&OQPKOAADLRUJ000J7U2UGNS2G".parse::<OidStr>();
         assert!(res.is_err());
-        assert!(matches!(res.unwrap_err(), Error::Base32Decode(_)));
+        assert_eq!(
+            res.unwrap_err(),
+            Error::InvalidValueChar {
+                index: 4,
+                char: '&'
+            }
+        );
+    }
+
+    #[test]
+    fn str_to_oid_err_prefix_char() {
+        let res = "T_T-0OQPKOAADLRUJ000J7U2UGNS2G".parse::<OidStr>();
+        assert_eq!(res.unwrap_err(), Error::InvalidPrefix { valid_until: 1 });
     }
 
     #[test]
@@ -245,7 +567,13 @@ mod oid_tests {
     fn str_to_oid_err_two_sep() {
         let res = "TST-0OQPKOAAD-LRUJ000J7U2UGNS2G".parse::<OidStr>();
         assert!(res.is_err());
-        assert!(matches!(res.unwrap_err(), Error::Base32Decode(_)));
+        assert_eq!(
+            res.unwrap_err(),
+            Error::WrongValueLength {
+                expected: 26,
+                found: 27
+            }
+        );
     }
 
     #[test]
@@ -274,6 +602,92 @@ mod oid_tests {
         assert_eq!("Tst-0OUS781P4LU7V000PA2A2BN1GC", &oid.to_string());
     }
 
+    #[test]
+    fn to_buf() {
+        let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+        assert_eq!(oid.to_buf(), "TST-0OQPKOAADLRUJ000J7U2UGNS2G");
+        assert_eq!(&*oid.to_buf(), oid.to_string());
+
+        let mut s = String::new();
+        oid.write_to(&mut s).unwrap();
+        assert_eq!(s, "TST-0OQPKOAADLRUJ000J7U2UGNS2G");
+    }
+
+    #[test]
+    fn str_to_oid_lowercase() {
+        let upper: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+        let lower: OidStr = "TST-0oqpkoaadlruj000j7u2ugns2g".parse().unwrap();
+        assert_eq!(upper, lower);
+        assert_eq!(lower.to_string(), "TST-0OQPKOAADLRUJ000J7U2UGNS2G");
+    }
+
+    #[test]
+    fn display_with_lowercase() {
+        let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+        let format = Format::new().value_case(Case::Lower);
+        assert_eq!(
+            oid.display_with(format).to_string(),
+            "TST-0oqpkoaadlruj000j7u2ugns2g"
+        );
+        assert_eq!(oid.to_buf_with(format), "TST-0oqpkoaadlruj000j7u2ugns2g");
+        assert_eq!(
+            oid.display_with(format).to_string().parse::<OidStr>(),
+            Ok(oid)
+        );
+    }
+
+    #[test]
+    fn encoding() {
+        use crate::encoding::Base62;
+
+        let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+        let format = Format::new().encoding(&Base62);
+        let s = oid.display_with(format).to_string();
+        assert_eq!(s.len(), 4 + 22);
+        assert_eq!(OidStr::parse_with(&s, format), Ok(oid.clone()));
+        assert_eq!(oid.to_buf_with(format), s.as_str());
+        assert!(s.parse::<OidStr>().is_err());
+    }
+
+    #[test]
+    fn separator() {
+        let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+        let format = Format::new().separator('_');
+        assert_eq!(oid.to_buf_with(format), "TST_0OQPKOAADLRUJ000J7U2UGNS2G");
+        assert_eq!(
+            OidStr::parse_with("TST_0OQPKOAADLRUJ000J7U2UGNS2G", format),
+            Ok(oid.clone())
+        );
+        assert_eq!(
+            OidStr::parse_with("TST-0OQPKOAADLRUJ000J7U2UGNS2G", format),
+            Err(Error::MissingSeparator)
+        );
+        assert_eq!(
+            OidStr::parse_with("TST-0OQPKOAADLRUJ000J7U2UGNS2G", format.alt_separator('-')),
+            Ok(oid)
+        );
+    }
+
+    #[test]
+    #[should_panic = "OID separator must not be ASCII alphanumeric"]
+    fn separator_alphanumeric() { let _ = Format::new().separator('A'); }
+
+    #[test]
+    #[should_panic = "OID separator must not be ASCII alphanumeric"]
+    fn alt_separator_alphanumeric() { let _ = Format::new().alt_separator('0'); }
+
+    #[test]
+    fn separator_multibyte_error_offset() {
+        let format = Format::new().separator('·');
+        assert_eq!(
+            OidStr::parse_with("TST·0OQPKOAADLRUJ000J7&2UGNS2G", format),
+            Err(Error::InvalidValueChar {
+                index: 23,
+                char: '&'
+            })
+        );
+    }
+
     #[test]
     fn hash() {
         use std::collections::HashMap;
@@ -283,3 +697,77 @@ mod oid_tests {
         map.insert(oid, "test");
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "ulid")]
+mod ulid_oidstr_tests {
+    use ::ulid::Ulid;
+
+    use super::*;
+    use crate::encoding::Crockford;
+
+    #[test]
+    fn with_ulid() {
+        let ulid: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+        let oid = OidStr::with_ulid("TST", ulid).unwrap();
+        assert_eq!(Ulid::from(&oid), ulid);
+        assert_eq!(
+            oid.display_with(Format::new().encoding(&Crockford))
+                .to_string(),
+            "TST-01ARZ3NDEKTSV4RRFFQ69G5FAV"
+        );
+        assert_eq!(OidStr::parse_ulid("TST-01ARZ3NDEKTSV4RRFFQ69G5FAV"), Ok(oid));
+    }
+}
+
+#[cfg(test)]
+mod oidstr_proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Without `alloc` prefixes can't spill to the heap
+    #[cfg(feature = "alloc")]
+    const PREFIX_REGEX: &str = "[A-Za-z0-9]{1,16}";
+    #[cfg(not(feature = "alloc"))]
+    const PREFIX_REGEX: &str = "[A-Za-z0-9]{1,8}";
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "\\PC*") {
+            let _ = s.parse::<OidStr>();
+        }
+
+        #[test]
+        fn from_str_toid_shaped_never_panics(s in "[A-Za-z0-9_]{0,8}-[0-9A-Za-z&-]{0,30}") {
+            let _ = s.parse::<OidStr>();
+        }
+
+        #[test]
+        fn from_str_roundtrip(pfx in PREFIX_REGEX, bytes in any::<[u8; 16]>()) {
+            let oid = OidStr::with_uuid(pfx.as_str(), Uuid::from_bytes(bytes)).unwrap();
+            prop_assert_eq!(oid.to_string().parse::<OidStr>(), Ok(oid));
+        }
+
+        #[test]
+        fn ord_matches_display(
+            a in (PREFIX_REGEX, any::<[u8; 16]>()),
+            b in (PREFIX_REGEX, any::<[u8; 16]>()),
+        ) {
+            let a = OidStr::with_uuid(a.0.as_str(), Uuid::from_bytes(a.1)).unwrap();
+            let b = OidStr::with_uuid(b.0.as_str(), Uuid::from_bytes(b.1)).unwrap();
+            prop_assert_eq!(a.cmp(&b), a.to_string().cmp(&b.to_string()));
+        }
+
+        #[test]
+        fn ord_same_prefix_matches_display(
+            pfx in PREFIX_REGEX,
+            a in any::<[u8; 16]>(),
+            b in any::<[u8; 16]>(),
+        ) {
+            let a = OidStr::with_uuid(pfx.as_str(), Uuid::from_bytes(a)).unwrap();
+            let b = OidStr::with_uuid(pfx.as_str(), Uuid::from_bytes(b)).unwrap();
+            prop_assert_eq!(a.cmp(&b), a.to_string().cmp(&b.to_string()));
+        }
+    }
+}
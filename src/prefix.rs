@@ -1,12 +1,24 @@
-use std::{
+use core::{
+    cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
     str::FromStr,
 };
 
+#[cfg(feature = "alloc")]
 use smallvec::SmallVec;
 
 use crate::error::{Error, Result};
+#[cfg(not(feature = "alloc"))]
+use crate::inline::InlineBytes;
+
+/// The number of bytes of a [`Prefix`] stored inline
+pub(crate) const INLINE_LEN: usize = 8;
+
+#[cfg(feature = "alloc")]
+type PrefixBytes = SmallVec<[u8; INLINE_LEN]>;
+#[cfg(not(feature = "alloc"))]
+type PrefixBytes = InlineBytes<INLINE_LEN>;
 
 #[inline]
 pub(crate) fn valid_prefix_char(c: u8) -> bool {
@@ -42,10 +54,11 @@ mod valid_prefix_char_tests {
 /// line" for the OID
 ///
 /// The prefix can store up to 8 bytes of 7-bit ASCII characters inline; a
-/// prefix of longer than 8 bytes will be "spilled" to the heap
+/// prefix of longer than 8 bytes will be "spilled" to the heap. Without the
+/// `alloc` feature prefixes are limited to 8 bytes.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Prefix {
-    bytes: SmallVec<[u8; 8]>,
+    bytes: PrefixBytes,
 }
 
 impl Prefix {
@@ -64,59 +77,73 @@ impl Prefix {
                     .unwrap(),
             });
         }
+        #[cfg(not(feature = "alloc"))]
+        if slice.len() > INLINE_LEN {
+            return Err(Error::PrefixByteLength);
+        }
         Ok(Self::from_slice_unchecked(slice))
     }
 
     /// Create a Prefix from a slice of bytes without checking the length or
     /// validity of the bytes
+    ///
+    /// # Panics
+    ///
+    /// Without the `alloc` feature, if the slice is longer than 8 bytes
     pub fn from_slice_unchecked(slice: &[u8]) -> Self {
         Self {
-            bytes: SmallVec::from_slice(slice),
+            bytes: PrefixBytes::from_slice(slice),
         }
     }
-}
 
-impl fmt::Display for Prefix {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Get the string representation of the Prefix
+    pub fn as_str(&self) -> &str {
         // SAFETY: self.bytes must not contain any invalid UTF-8. We don't expose the
         // inner byte array for manipulation, and the only way to construct self
         // checks for a subset of 7-bit ASCII which itself is a subset of UTF-8
-        unsafe {
-            write!(
-                f,
-                "{}",
-                std::str::from_utf8_unchecked(self.bytes.as_slice())
-            )
-        }
+        unsafe { core::str::from_utf8_unchecked(self.bytes.as_slice()) }
     }
 }
 
+impl fmt::Display for Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
 impl FromStr for Prefix {
     type Err = Error;
 
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> { Self::from_slice(s.as_bytes()) }
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> { Self::from_slice(s.as_bytes()) }
 }
 
 impl TryFrom<&[u8]> for Prefix {
     type Error = Error;
 
-    fn try_from(slice: &[u8]) -> std::result::Result<Self, Self::Error> { Self::from_slice(slice) }
+    fn try_from(slice: &[u8]) -> core::result::Result<Self, Self::Error> { Self::from_slice(slice) }
 }
 
 impl TryFrom<&str> for Prefix {
     type Error = Error;
 
-    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> { s.parse() }
+    fn try_from(s: &str) -> core::result::Result<Self, Self::Error> { s.parse() }
 }
 
 impl Hash for Prefix {
     fn hash<H: Hasher>(&self, state: &mut H) { self.bytes.hash(state); }
 }
 
+/// Prefixes are ordered bytewise, i.e. the same as their string representation
+impl PartialOrd for Prefix {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Prefix {
+    fn cmp(&self, other: &Self) -> Ordering { self.as_str().cmp(other.as_str()) }
+}
+
 #[cfg(test)]
+#[allow(clippy::byte_char_slices)]
 mod prefix_tests {
     use super::*;
-    use smallvec::smallvec;
 
     #[test]
     fn from_str() {
@@ -125,7 +152,7 @@ mod prefix_tests {
         assert_eq!(
             pfx.unwrap(),
             Prefix {
-                bytes: smallvec![b'P', b'F', b'X']
+                bytes: PrefixBytes::from_slice(b"PFX")
             }
         );
     }
@@ -144,7 +171,7 @@ mod prefix_tests {
         assert_eq!(
             pfx.unwrap(),
             Prefix {
-                bytes: smallvec![b'P', b'F', b'x']
+                bytes: PrefixBytes::from_slice(b"PFx")
             }
         );
     }
@@ -157,7 +184,7 @@ mod prefix_tests {
         assert_eq!(
             pfx.unwrap(),
             Prefix {
-                bytes: SmallVec::from_slice(&arr)
+                bytes: PrefixBytes::from_slice(&arr)
             }
         );
     }
@@ -178,14 +205,53 @@ mod prefix_tests {
         assert_eq!(
             pfx.unwrap(),
             Prefix {
-                bytes: smallvec![b'P', b'F', b'x']
+                bytes: PrefixBytes::from_slice(b"PFx")
             }
         );
     }
 
+    #[test]
+    #[cfg(not(feature = "alloc"))]
+    fn from_str_err_too_long() {
+        assert_eq!(
+            "PrefixTooLong".parse::<Prefix>(),
+            Err(Error::PrefixByteLength)
+        );
+    }
+
     #[test]
     fn to_string() {
         let pfx: Prefix = "PFx".parse().unwrap();
         assert_eq!("PFx".to_string(), pfx.to_string());
     }
+
+    #[test]
+    fn ord() {
+        let mut pfxs: Vec<Prefix> = ["a", "Tsu", "Tst", "B", "Ts"]
+            .iter()
+            .map(|p| p.parse().unwrap())
+            .collect();
+        pfxs.sort();
+        let pfxs: Vec<&str> = pfxs.iter().map(Prefix::as_str).collect();
+        assert_eq!(pfxs, ["B", "Ts", "Tst", "Tsu", "a"]);
+    }
+}
+
+#[cfg(test)]
+mod prefix_proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_str_never_panics(s in "\\PC*") {
+            let _ = s.parse::<Prefix>();
+        }
+
+        #[test]
+        fn from_slice_never_panics(bytes in any::<Vec<u8>>()) {
+            let _ = Prefix::from_slice(&bytes);
+        }
+    }
 }
@@ -0,0 +1,282 @@
+//! Bounds of TOIDs for time-based range scans
+//!
+//! UUIDv7s are ordered by their millisecond timestamp first, so all TOIDs
+//! created within a time window lie between the smallest possible UUIDv7 of its
+//! start and the largest possible UUIDv7 of its end. An [`OidRange`] (or an
+//! [`OidStrRange`] for a given prefix) holds those bounds, and implements
+//! [`RangeBounds`] so it can be used directly with e.g. `BTreeMap::range`, or
+//! its bounds used as the parameters of a database query.
+//!
+//! Times only have millisecond precision, as is the case for UUIDv7s.
+//!
+//! > **NOTE:** The bounds only compare the UUIDs, so a TOID with a UUID of
+//! > another version may still be contained in a range.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! # use std::{collections::BTreeMap, time::{Duration, SystemTime}};
+//! # use typed_oid::{Oid, OidPrefix, OidRange};
+//! struct Order;
+//! impl OidPrefix for Order {}
+//!
+//! let t = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+//!
+//! let mut orders = BTreeMap::new();
+//! for secs in [1_700_000_000, 1_700_000_060, 1_700_000_120] {
+//!     orders.insert(Oid::<Order>::min_at(t(secs)), secs);
+//! }
+//!
+//! let range = OidRange::from_time_range(t(1_700_000_030)..t(1_700_000_120));
+//! let found: Vec<_> = orders.range(range).map(|(_, secs)| *secs).collect();
+//! assert_eq!(found, [1_700_000_060]);
+//! # }
+//! ```
+
+use core::{
+    fmt,
+    ops::{Bound, RangeBounds},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    error::Result, timestamp::uuid_v7_bounds, Error, Oid, OidPrefix, OidStr, Prefix, UnixTimestamp,
+};
+
+/// A range of [`Oid`]s created within a time window
+pub struct OidRange<P> {
+    start: Bound<Oid<P>>,
+    end: Bound<Oid<P>>,
+}
+
+impl<P: OidPrefix> OidRange<P> {
+    /// Create the range of `Oid`s with a UUIDv7 created within `range`, e.g.
+    /// `start..end` or `start..`
+    pub fn from_time_range<T, R>(range: R) -> Self
+    where
+        T: UnixTimestamp,
+        R: RangeBounds<T>,
+    {
+        let (start, end) = time_bounds(&range);
+        Self {
+            start: map_bound(start, Oid::with_uuid),
+            end: map_bound(end, Oid::with_uuid),
+        }
+    }
+
+    /// Whether `oid` is contained in the range
+    pub fn contains(&self, oid: &Oid<P>) -> bool { RangeBounds::contains(self, oid) }
+}
+
+impl<P> RangeBounds<Oid<P>> for OidRange<P> {
+    fn start_bound(&self) -> Bound<&Oid<P>> { as_ref(&self.start) }
+
+    fn end_bound(&self) -> Bound<&Oid<P>> { as_ref(&self.end) }
+}
+
+impl<P> fmt::Debug for OidRange<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidRange")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+// Must manually implement these because of the PhantomData of `Oid`, see
+// rust#26925
+impl<P> Clone for OidRange<P> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<P> Copy for OidRange<P> {}
+
+impl<P> PartialEq for OidRange<P> {
+    fn eq(&self, other: &Self) -> bool { self.start == other.start && self.end == other.end }
+}
+
+impl<P> Eq for OidRange<P> {}
+
+/// A range of [`OidStr`]s with a given [`Prefix`] created within a time window
+///
+/// Unlike an [`OidRange`] the bounds are always limited to the prefix, so
+/// e.g. a range without a start doesn't contain OIDs with a smaller prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidStrRange {
+    start: Bound<OidStr>,
+    end: Bound<OidStr>,
+}
+
+impl OidStrRange {
+    /// Create the range of OIDs with a given [`Prefix`] and a UUIDv7 created
+    /// within `range`, e.g. `start..end` or `start..`
+    pub fn from_time_range<P, T, R>(prefix: P, range: R) -> Result<Self>
+    where
+        P: TryInto<Prefix, Error = Error>,
+        T: UnixTimestamp,
+        R: RangeBounds<T>,
+    {
+        let prefix: Prefix = prefix.try_into()?;
+        let (start, end) = time_bounds(&range);
+        let with_uuid = |uuid| OidStr::with_uuid(prefix.as_str(), uuid).expect("valid prefix");
+        Ok(Self {
+            start: match start {
+                Bound::Unbounded => Bound::Included(with_uuid(Uuid::nil())),
+                start => map_bound(start, with_uuid),
+            },
+            end: match end {
+                Bound::Unbounded => Bound::Included(with_uuid(Uuid::from_u128(u128::MAX))),
+                end => map_bound(end, with_uuid),
+            },
+        })
+    }
+
+    /// Whether `oid` is contained in the range
+    pub fn contains(&self, oid: &OidStr) -> bool { RangeBounds::contains(self, oid) }
+}
+
+impl RangeBounds<OidStr> for OidStrRange {
+    fn start_bound(&self) -> Bound<&OidStr> { as_ref(&self.start) }
+
+    fn end_bound(&self) -> Bound<&OidStr> { as_ref(&self.end) }
+}
+
+/// The bounds of the UUIDv7s created within `range`
+fn time_bounds<T: UnixTimestamp>(range: &impl RangeBounds<T>) -> (Bound<Uuid>, Bound<Uuid>) {
+    let start = match range.start_bound() {
+        Bound::Included(t) => Bound::Included(uuid_v7_bounds(t).0),
+        Bound::Excluded(t) => Bound::Excluded(uuid_v7_bounds(t).1),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(t) => Bound::Included(uuid_v7_bounds(t).1),
+        Bound::Excluded(t) => Bound::Excluded(uuid_v7_bounds(t).0),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (start, end)
+}
+
+// `Bound::map` and `Bound::as_ref` require a newer MSRV
+fn map_bound<T, U>(bound: Bound<T>, f: impl FnOnce(T) -> U) -> Bound<U> {
+    match bound {
+        Bound::Included(t) => Bound::Included(f(t)),
+        Bound::Excluded(t) => Bound::Excluded(f(t)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+fn as_ref<T>(bound: &Bound<T>) -> Bound<&T> {
+    match bound {
+        Bound::Included(t) => Bound::Included(t),
+        Bound::Excluded(t) => Bound::Excluded(t),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod range_tests {
+    use std::{
+        collections::BTreeSet,
+        time::{Duration, SystemTime},
+    };
+
+    use super::*;
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    fn at(millis: u64) -> SystemTime { SystemTime::UNIX_EPOCH + Duration::from_millis(millis) }
+
+    fn oid(uuid: &str) -> Oid<Tst> { Oid::try_with_uuid(uuid).unwrap() }
+
+    #[test]
+    fn min_max_at() {
+        let t = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        assert_eq!(
+            Oid::<Tst>::min_at(t),
+            oid("018bcfe5-687b-7000-8000-000000000000")
+        );
+        assert_eq!(
+            Oid::<Tst>::max_at(t),
+            oid("018bcfe5-687b-7fff-bfff-ffffffffffff")
+        );
+        assert_eq!(
+            OidStr::min_at("Tst", t).unwrap().uuid(),
+            Oid::<Tst>::min_at(t).uuid()
+        );
+        assert_eq!(
+            OidStr::max_at("Tst", t).unwrap().uuid(),
+            Oid::<Tst>::max_at(t).uuid()
+        );
+        assert!(OidStr::min_at("Tst-", t).is_err());
+    }
+
+    #[test]
+    fn contains() {
+        let range = OidRange::<Tst>::from_time_range(at(1_700_000_000_123)..at(1_700_000_000_125));
+        assert!(!range.contains(&oid("018bcfe5-687a-7fff-bfff-ffffffffffff")));
+        assert!(range.contains(&oid("018bcfe5-687b-7000-8000-000000000000")));
+        assert!(range.contains(&oid("018bcfe5-687c-7c7f-8000-ca84a12ee183")));
+        assert!(range.contains(&oid("018bcfe5-687c-7fff-bfff-ffffffffffff")));
+        assert!(!range.contains(&oid("018bcfe5-687d-7000-8000-000000000000")));
+
+        let range = OidRange::<Tst>::from_time_range(at(1_700_000_000_123)..=at(1_700_000_000_125));
+        assert!(range.contains(&oid("018bcfe5-687d-7fff-bfff-ffffffffffff")));
+
+        let range = OidRange::<Tst>::from_time_range((
+            Bound::Excluded(at(1_700_000_000_123)),
+            Bound::Unbounded,
+        ));
+        assert!(!range.contains(&oid("018bcfe5-687b-7fff-bfff-ffffffffffff")));
+        assert!(range.contains(&oid("018bcfe5-687c-7000-8000-000000000000")));
+        assert!(range.contains(&oid("ffffffff-ffff-7fff-bfff-ffffffffffff")));
+
+        let range = OidRange::<Tst>::from_time_range(..at(1_700_000_000_123));
+        assert!(range.contains(&oid("00000000-0000-0000-0000-000000000000")));
+        assert!(!range.contains(&oid("018bcfe5-687b-7000-8000-000000000000")));
+    }
+
+    #[test]
+    fn btree_range() {
+        let oids: BTreeSet<Oid<Tst>> = (0..10).map(|i| Oid::min_at(at(1_000 * i))).collect();
+        let found: Vec<_> = oids
+            .range(OidRange::from_time_range(at(2_000)..at(5_000)))
+            .copied()
+            .collect();
+        assert_eq!(
+            found,
+            [
+                Oid::min_at(at(2_000)),
+                Oid::min_at(at(3_000)),
+                Oid::min_at(at(4_000))
+            ]
+        );
+    }
+
+    #[test]
+    fn oidstr_range() {
+        let range = OidStrRange::from_time_range("Tst", at(2_000)..).unwrap();
+        assert!(range.contains(&OidStr::min_at("Tst", at(2_000)).unwrap()));
+        assert!(!range.contains(&OidStr::max_at("Tst", at(1_999)).unwrap()));
+        assert!(!range.contains(&OidStr::min_at("Tsu", at(3_000)).unwrap()));
+
+        let oids: BTreeSet<OidStr> = ["Tss", "Tst", "Tsu"]
+            .iter()
+            .flat_map(|p| (0..4).map(move |i| OidStr::min_at(*p, at(1_000 * i)).unwrap()))
+            .collect();
+        let range = OidStrRange::from_time_range("Tst", ..at(2_000)).unwrap();
+        let found: Vec<_> = oids.range(range).cloned().collect();
+        assert_eq!(
+            found,
+            [
+                OidStr::min_at("Tst", at(0)).unwrap(),
+                OidStr::min_at("Tst", at(1_000)).unwrap()
+            ]
+        );
+
+        assert!(OidStrRange::from_time_range("Tst-", at(0)..).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+//! [`rusqlite`] support for TOIDs
+//!
+//! An [`Oid`] is bound as a 16 byte BLOB of its UUID, or as TOID text when
+//! wrapped in [`AsText`]. Either form can be read back into an `Oid`, or into
+//! an `AsText`. An [`OidStr`] is always bound and read as TOID text, as its
+//! prefix isn't part of the type.
+//!
+//! A TOID whose prefix doesn't match (or which is otherwise invalid) is
+//! reported as [`FromSqlError::Other`] holding the [`Error`](crate::Error).
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use rusqlite::Connection;
+//! # use typed_oid::{rusqlite::AsText, Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! let conn = Connection::open_in_memory().unwrap();
+//! let oid: Oid<Usr> = "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+//!
+//! let blob: Vec<u8> = conn.query_row("SELECT ?", [oid], |row| row.get(0)).unwrap();
+//! assert_eq!(blob, oid.uuid().as_bytes());
+//!
+//! let text: String = conn
+//!     .query_row("SELECT ?", [AsText(oid)], |row| row.get(0))
+//!     .unwrap();
+//! assert_eq!(text, "Usr-0OUS781P4LU7V000PA2A2BN1GC");
+//!
+//! let parsed: Oid<Usr> = conn.query_row("SELECT ?", [text], |row| row.get(0)).unwrap();
+//! assert_eq!(parsed.uuid(), oid.uuid());
+//! ```
+
+use core::fmt;
+
+use ::rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use uuid::Uuid;
+
+use crate::{Oid, OidPrefix, OidStr};
+
+/// Binds an [`Oid`] as TOID text rather than a BLOB
+pub struct AsText<P>(pub Oid<P>);
+
+impl<P> fmt::Debug for AsText<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AsText").field(&self.0).finish()
+    }
+}
+
+// Implemented manually to avoid requiring `P: Copy`, as for `Oid`
+impl<P> Copy for AsText<P> {}
+
+impl<P> Clone for AsText<P> {
+    fn clone(&self) -> Self { *self }
+}
+
+// Implemented manually to avoid requiring `P: PartialEq`, as for `Oid`
+impl<P> PartialEq for AsText<P> {
+    fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+}
+
+impl<P> Eq for AsText<P> {}
+
+impl<P> From<Oid<P>> for AsText<P> {
+    fn from(oid: Oid<P>) -> Self { Self(oid) }
+}
+
+impl<P> From<AsText<P>> for Oid<P> {
+    fn from(text: AsText<P>) -> Self { text.0 }
+}
+
+impl<P: OidPrefix> ToSql for Oid<P> {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(&self.uuid().as_bytes()[..]))
+    }
+}
+
+impl<P: OidPrefix> FromSql for Oid<P> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(bytes) => Uuid::from_slice(bytes)
+                .map(Self::with_uuid)
+                .map_err(|_| FromSqlError::InvalidBlobSize {
+                    expected_size: 16,
+                    blob_size: bytes.len(),
+                }),
+            ValueRef::Text(_) => value
+                .as_str()?
+                .parse()
+                .map_err(|e| FromSqlError::Other(Box::new(e))),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl<P: OidPrefix> ToSql for AsText<P> {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_string()))
+    }
+}
+
+impl<P: OidPrefix> FromSql for AsText<P> {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Oid::column_result(value).map(Self)
+    }
+}
+
+impl ToSql for OidStr {
+    fn to_sql(&self) -> ::rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for OidStr {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value.as_str()?.parse().map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod rusqlite_tests {
+    use ::rusqlite::{params, Connection};
+
+    use super::*;
+    use crate::Error;
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+
+    fn connect() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE t (b BLOB, s TEXT)", []).unwrap();
+        conn
+    }
+
+    fn select<T: FromSql>(conn: &Connection, column: &str) -> ::rusqlite::Result<T> {
+        conn.query_row(&format!("SELECT {column} FROM t"), [], |row| row.get(0))
+    }
+
+    #[test]
+    fn oid_blob_and_text() {
+        let conn = connect();
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        conn.execute("INSERT INTO t VALUES (?, ?)", params![oid, AsText(oid)])
+            .unwrap();
+
+        assert_eq!(select::<Vec<u8>>(&conn, "b").unwrap(), oid.uuid().as_bytes());
+        assert_eq!(select::<String>(&conn, "s").unwrap(), TOID);
+        assert_eq!(select::<Oid<Tst>>(&conn, "b").unwrap(), oid);
+        assert_eq!(select::<Oid<Tst>>(&conn, "s").unwrap(), oid);
+        assert_eq!(select::<AsText<Tst>>(&conn, "b").unwrap(), AsText(oid));
+        assert_eq!(select::<AsText<Tst>>(&conn, "s").unwrap(), AsText(oid));
+    }
+
+    #[test]
+    fn as_text_eq() {
+        struct Usr;
+        impl OidPrefix for Usr {}
+
+        let oid: Oid<Usr> = "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(AsText(oid), AsText::from(oid));
+        assert_ne!(AsText(oid), AsText(Oid::with_uuid(Uuid::nil())));
+    }
+
+    #[test]
+    fn oidstr_text() {
+        let conn = connect();
+        let oid: OidStr = TOID.parse().unwrap();
+        conn.execute("INSERT INTO t (s) VALUES (?)", [&oid]).unwrap();
+
+        assert_eq!(select::<String>(&conn, "s").unwrap(), TOID);
+        assert_eq!(select::<OidStr>(&conn, "s").unwrap(), oid);
+    }
+
+    #[test]
+    fn errors() {
+        let conn = connect();
+        conn.execute(
+            "INSERT INTO t VALUES (x'0102', 'Other-0OUS781P4LU7V000PA2A2BN1GC')",
+            [],
+        )
+        .unwrap();
+
+        match select::<Oid<Tst>>(&conn, "s").unwrap_err() {
+            ::rusqlite::Error::FromSqlConversionFailure(_, _, e) => assert_eq!(
+                e.downcast_ref::<Error>(),
+                Some(&Error::UnexpectedPrefix {
+                    expected: "Tst",
+                    found: "Other".parse().unwrap()
+                })
+            ),
+            e => panic!("unexpected error {e:?}"),
+        }
+        match select::<Oid<Tst>>(&conn, "b").unwrap_err() {
+            ::rusqlite::Error::FromSqlConversionFailure(_, _, e) => assert!(matches!(
+                e.downcast_ref::<FromSqlError>(),
+                Some(FromSqlError::InvalidBlobSize {
+                    expected_size: 16,
+                    blob_size: 2
+                })
+            )),
+            e => panic!("unexpected error {e:?}"),
+        }
+        assert!(matches!(
+            select::<Oid<Tst>>(&conn, "1").unwrap_err(),
+            ::rusqlite::Error::InvalidColumnType(..)
+        ));
+        assert!(matches!(
+            select::<OidStr>(&conn, "b").unwrap_err(),
+            ::rusqlite::Error::InvalidColumnType(..)
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by the schema generating integrations
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::{encoding::Base32Hex, Case, OidEncoding, OidPrefix, VALUE_LEN};
+
+/// Builds the pattern matching a TOID of `P`, e.g.
+/// `^(?:Usr|user)-[0-9A-V]{26}$`
+pub(crate) fn oid_pattern<P: OidPrefix>() -> String {
+    let mut pattern = "^".to_string();
+
+    let aliases = P::aliases();
+    if !aliases.is_empty() {
+        pattern.push_str("(?:");
+    }
+    for (i, prefix) in core::iter::once(&P::prefix()).chain(aliases).enumerate() {
+        if i > 0 {
+            pattern.push('|');
+        }
+        for c in prefix.chars() {
+            if P::ignore_prefix_case() && c.is_ascii_alphabetic() {
+                pattern.push('[');
+                pattern.push(c.to_ascii_uppercase());
+                pattern.push(c.to_ascii_lowercase());
+                pattern.push(']');
+            } else {
+                pattern.push(c);
+            }
+        }
+    }
+    if !aliases.is_empty() {
+        pattern.push(')');
+    }
+
+    match P::alt_separator() {
+        Some(alt) => {
+            pattern.push_str("(?:");
+            push_escaped(&mut pattern, P::separator());
+            pattern.push('|');
+            push_escaped(&mut pattern, alt);
+            pattern.push(')');
+        }
+        None => push_escaped(&mut pattern, P::separator()),
+    }
+
+    let encoding = P::encoding();
+    pattern.push_str(encoding.char_class(P::value_case()));
+    pattern.push_str(&format!("{{{}}}$", encoding.value_len()));
+    pattern
+}
+
+/// Pushes `c` to `pattern` escaping it if it has a special meaning in a
+/// regular expression
+fn push_escaped(pattern: &mut String, c: char) {
+    if "^$\\.*+?()[]{}|/".contains(c) {
+        pattern.push('\\');
+    }
+    pattern.push(c);
+}
+
+/// The pattern matching an [`OidStr`](crate::OidStr) in the default format
+pub(crate) fn oidstr_pattern() -> String {
+    format!("^[0-9A-Za-z]+-{}{{{VALUE_LEN}}}$", Base32Hex.char_class(Case::Upper))
+}
+
+#[cfg(test)]
+mod schema_tests {
+    use super::*;
+    use crate::encoding::Base62;
+
+    struct Usr;
+    impl OidPrefix for Usr {
+        fn aliases() -> &'static [&'static str] { &["user"] }
+
+        fn ignore_prefix_case() -> bool { true }
+    }
+
+    struct Evt;
+    impl OidPrefix for Evt {
+        fn encoding() -> &'static dyn OidEncoding { &Base62 }
+
+        fn separator() -> char { '.' }
+
+        fn alt_separator() -> Option<char> { Some('-') }
+    }
+
+    struct Low;
+    impl OidPrefix for Low {
+        fn value_case() -> Case { Case::Lower }
+    }
+
+    #[test]
+    fn oid_patterns() {
+        assert_eq!(
+            oid_pattern::<Usr>(),
+            "^(?:[Uu][Ss][Rr]|[Uu][Ss][Ee][Rr])-[0-9A-V]{26}$"
+        );
+        assert_eq!(oid_pattern::<Evt>(), "^Evt(?:\\.|-)[0-9A-Za-z]{22}$");
+        assert_eq!(oid_pattern::<Low>(), "^Low-[0-9a-v]{26}$");
+    }
+
+    #[test]
+    fn oidstr() {
+        assert_eq!(oidstr_pattern(), "^[0-9A-Za-z]+-[0-9A-V]{26}$");
+    }
+}
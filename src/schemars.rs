@@ -0,0 +1,97 @@
+//! [`schemars`](::schemars) support for TOIDs
+//!
+//! Both [`Oid`] and [`OidStr`] are described as strings with a `pattern`
+//! matching the rendered TOID. For an `Oid<P>` the pattern is built from `P`,
+//! i.e. its prefix (and any [`OidPrefix::aliases`]), separator(s), encoding
+//! and value case, and each prefix type gets its own schema name. An `OidStr`
+//! matches any prefix with the default format.
+
+use alloc::{borrow::Cow, format};
+
+use ::schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{
+    schema::{oid_pattern, oidstr_pattern},
+    Oid, OidPrefix, OidStr,
+};
+
+impl<P: OidPrefix> JsonSchema for Oid<P> {
+    fn schema_name() -> Cow<'static, str> { format!("Oid_{}", P::prefix()).into() }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!("typed_oid::Oid<{}>", core::any::type_name::<P>()).into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": oid_pattern::<P>(),
+        })
+    }
+}
+
+impl JsonSchema for OidStr {
+    fn schema_name() -> Cow<'static, str> { "OidStr".into() }
+
+    fn schema_id() -> Cow<'static, str> { "typed_oid::OidStr".into() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": oidstr_pattern(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod schemars_tests {
+    use ::schemars::{schema_for, SchemaGenerator};
+    use serde_json::json;
+
+    use super::*;
+    use crate::Case;
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    struct Low;
+    impl OidPrefix for Low {
+        fn value_case() -> Case { Case::Lower }
+    }
+
+    #[test]
+    fn oid_schema() {
+        assert_eq!(
+            schema_for!(Oid<Tst>).as_value(),
+            &json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "Oid_Tst",
+                "type": "string",
+                "pattern": "^Tst-[0-9A-V]{26}$",
+            })
+        );
+    }
+
+    #[test]
+    fn schema_per_prefix() {
+        let mut generator = SchemaGenerator::default();
+        let tst = generator.subschema_for::<Oid<Tst>>();
+        let low = generator.subschema_for::<Oid<Low>>();
+        assert_eq!(tst.as_value(), &json!({ "$ref": "#/$defs/Oid_Tst" }));
+        assert_eq!(low.as_value(), &json!({ "$ref": "#/$defs/Oid_Low" }));
+        assert_eq!(generator.definitions().len(), 2);
+    }
+
+    #[test]
+    fn oidstr_schema() {
+        assert_eq!(
+            schema_for!(OidStr).as_value(),
+            &json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "OidStr",
+                "type": "string",
+                "pattern": "^[0-9A-Za-z]+-[0-9A-V]{26}$",
+            })
+        );
+    }
+}
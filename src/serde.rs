@@ -0,0 +1,301 @@
+//! Serde support for TOIDs
+//!
+//! The `Serialize` and `Deserialize` impls of [`Oid`](crate::Oid) and
+//! [`OidStr`](crate::OidStr) use the TOID string for human readable formats,
+//! and a compact byte representation otherwise. The modules here provide
+//! alternative representations for use with `#[serde(with = "...")]`:
+//!
+//! - [`lenient`] accepts either a TOID or a bare UUID
+//! - [`as_uuid`] (de)serializes only the UUID
+//! - [`structured`] (de)serializes a struct of the prefix and UUID
+
+use core::{fmt, marker::PhantomData, str::FromStr};
+
+use ::serde::{
+    de::{self, SeqAccess, Unexpected, Visitor},
+    ser::Serializer,
+};
+#[cfg(feature = "alloc")]
+use smallvec::SmallVec;
+use uuid::Uuid;
+
+use crate::{buf::OidBuf, error::Error, prefix::Prefix};
+#[cfg(not(feature = "alloc"))]
+use crate::{inline::InlineBytes, prefix::INLINE_LEN};
+
+pub mod as_uuid;
+pub mod lenient;
+pub mod structured;
+
+/// The compact representation of an `OidStr`, i.e. the prefix bytes followed by
+/// the 16 UUID bytes
+#[cfg(feature = "alloc")]
+type CompactBytes = SmallVec<[u8; 32]>;
+#[cfg(not(feature = "alloc"))]
+type CompactBytes = InlineBytes<{ INLINE_LEN + 16 }>;
+
+/// Deserializes a TOID from a string without requiring an allocation for the
+/// string itself
+pub(crate) struct FromStrVisitor<T>(PhantomData<T>);
+
+impl<T> FromStrVisitor<T> {
+    pub(crate) fn new() -> Self { Self(PhantomData) }
+}
+
+impl<'de, T> Visitor<'de> for FromStrVisitor<T>
+where
+    T: FromStr<Err = Error>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a TOID string") }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> { v.parse().map_err(E::custom) }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+/// A TOID which has a compact byte representation for formats which aren't
+/// human readable
+pub(crate) trait FromCompact: FromStr<Err = Error> {
+    /// Decodes the compact representation, or returns `None` if `bytes` isn't
+    /// one
+    fn from_compact(bytes: &[u8]) -> Option<Result<Self, Error>>;
+}
+
+/// Deserializes a TOID from either its compact byte representation or a string
+///
+/// Formats which encode strings and bytes the same way (such as bincode and
+/// postcard) can thus still read TOIDs that were serialized as strings.
+pub(crate) struct CompactVisitor<T>(PhantomData<T>);
+
+impl<T> CompactVisitor<T> {
+    pub(crate) fn new() -> Self { Self(PhantomData) }
+}
+
+impl<'de, T> Visitor<'de> for CompactVisitor<T>
+where
+    T: FromCompact,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TOID bytes or a TOID string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> { v.parse().map_err(E::custom) }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        match T::from_compact(v) {
+            Some(res) => res.map_err(E::custom),
+            None => FromStrVisitor::new().visit_bytes(v),
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = CompactBytes::new();
+        while let Some(byte) = seq.next_element()? {
+            #[cfg(feature = "alloc")]
+            bytes.push(byte);
+            #[cfg(not(feature = "alloc"))]
+            if !bytes.try_extend_from_slice(&[byte]) {
+                return Err(de::Error::invalid_length(bytes.len() + 1, &self));
+            }
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+/// Serializes the string representation of a TOID, which is written directly
+/// to the serializer when it doesn't fit in an [`OidBuf`] (i.e. a long prefix
+/// without the `alloc` feature)
+pub(crate) fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display + ?Sized,
+    S: Serializer,
+{
+    match OidBuf::try_build(|w| write!(w, "{value}")) {
+        Some(buf) => serializer.serialize_str(&buf),
+        None => serializer.collect_str(value),
+    }
+}
+
+/// Serializes the compact representation of an `OidStr`
+pub(crate) fn serialize_prefixed<S: Serializer>(
+    prefix: &Prefix,
+    uuid: &Uuid,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut bytes = CompactBytes::from_slice(prefix.as_str().as_bytes());
+    #[cfg(feature = "alloc")]
+    bytes.extend_from_slice(uuid.as_bytes());
+    // Without `alloc` prefixes are at most `INLINE_LEN` bytes, so the UUID always fits
+    #[cfg(not(feature = "alloc"))]
+    assert!(bytes.try_extend_from_slice(uuid.as_bytes()));
+    serializer.serialize_bytes(&bytes)
+}
+
+#[cfg(test)]
+mod compact_tests {
+    use ::serde::{de::DeserializeOwned, Serialize};
+    use ciborium::tag::Required;
+    use proptest::prelude::*;
+
+    use crate::{Oid, OidPrefix, OidStr};
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+
+    fn oid() -> Oid<Tst> { TOID.parse().unwrap() }
+
+    fn oidstr() -> OidStr { TOID.parse().unwrap() }
+
+    fn bincode<T: Serialize + DeserializeOwned>(value: &T) -> (Vec<u8>, T) {
+        let config = bincode::config::standard();
+        let bytes = bincode::serde::encode_to_vec(value, config).unwrap();
+        let (decoded, _) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+        (bytes, decoded)
+    }
+
+    fn postcard<T: Serialize + DeserializeOwned>(value: &T) -> (Vec<u8>, T) {
+        let bytes = postcard::to_allocvec(value).unwrap();
+        let decoded = postcard::from_bytes(&bytes).unwrap();
+        (bytes, decoded)
+    }
+
+    fn cbor<T: Serialize + DeserializeOwned>(value: &T) -> (Vec<u8>, T) {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        let decoded = ciborium::from_reader(&bytes[..]).unwrap();
+        (bytes, decoded)
+    }
+
+    fn compact_oid(prefix: &[u8], oid: &Oid<Tst>) -> Vec<u8> {
+        [prefix, oid.uuid().as_bytes()].concat()
+    }
+
+    #[test]
+    fn bincode_roundtrip() {
+        let (bytes, decoded) = bincode(&oid());
+        assert_eq!(bytes, compact_oid(&[16], &oid()));
+        assert_eq!(decoded, oid());
+
+        let (bytes, decoded) = bincode(&oidstr());
+        assert_eq!(bytes, compact_oid(b"\x13Tst", &oid()));
+        assert_eq!(decoded, oidstr());
+    }
+
+    #[test]
+    fn postcard_roundtrip() {
+        let (bytes, decoded) = postcard(&oid());
+        assert_eq!(bytes, compact_oid(&[16], &oid()));
+        assert_eq!(decoded, oid());
+
+        let (bytes, decoded) = postcard(&oidstr());
+        assert_eq!(bytes, compact_oid(b"\x13Tst", &oid()));
+        assert_eq!(decoded, oidstr());
+    }
+
+    #[test]
+    fn cbor_roundtrip() {
+        // Major type 2 (byte string) with the length in the lower bits
+        let (bytes, decoded) = cbor(&oid());
+        assert_eq!(bytes, compact_oid(&[0x50], &oid()));
+        assert_eq!(decoded, oid());
+
+        let (bytes, decoded) = cbor(&oidstr());
+        assert_eq!(bytes, compact_oid(b"\x53Tst", &oid()));
+        assert_eq!(decoded, oidstr());
+    }
+
+    #[test]
+    fn cbor_uuid_tag() {
+        // Tag 37 marks a byte string as a UUID
+        let (bytes, decoded) = cbor(&Required::<_, 37>(oid()));
+        assert_eq!(bytes, compact_oid(&[0xd8, 37, 0x50], &oid()));
+        assert_eq!(decoded.0, oid());
+
+        let untagged: Oid<Tst> = ciborium::from_reader(&bytes[..]).unwrap();
+        assert_eq!(untagged, oid());
+    }
+
+    #[test]
+    fn cbor_byte_array() {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(oid().uuid().as_bytes(), &mut bytes).unwrap();
+        let decoded: Oid<Tst> = ciborium::from_reader(&bytes[..]).unwrap();
+        assert_eq!(decoded, oid());
+    }
+
+    #[test]
+    fn string_form() {
+        let config = bincode::config::standard();
+        let bytes = bincode::serde::encode_to_vec(TOID, config).unwrap();
+        let (decoded, _): (Oid<Tst>, _) =
+            bincode::serde::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(decoded, oid());
+        let (decoded, _): (OidStr, _) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(decoded, oidstr());
+
+        let bytes = postcard::to_allocvec(TOID).unwrap();
+        assert_eq!(postcard::from_bytes::<Oid<Tst>>(&bytes).unwrap(), oid());
+        assert_eq!(postcard::from_bytes::<OidStr>(&bytes).unwrap(), oidstr());
+    }
+
+    #[test]
+    fn unexpected_prefix() {
+        let bytes = postcard::to_allocvec("Other-0OUS781P4LU7V000PA2A2BN1GC").unwrap();
+        assert!(postcard::from_bytes::<Oid<Tst>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn invalid_length() {
+        let bytes = postcard::to_allocvec(&[0u8; 15][..]).unwrap();
+        assert!(postcard::from_bytes::<Oid<Tst>>(&bytes).is_err());
+        assert!(postcard::from_bytes::<OidStr>(&bytes).is_err());
+    }
+
+    #[test]
+    fn human_readable() {
+        assert_eq!(
+            serde_json::to_string(&oid()).unwrap(),
+            format!("\"{TOID}\"")
+        );
+        assert_eq!(
+            serde_json::to_string(&oidstr()).unwrap(),
+            format!("\"{TOID}\"")
+        );
+    }
+
+    #[test]
+    fn human_readable_long_prefix() {
+        struct Long;
+        impl OidPrefix for Long {
+            fn prefix() -> &'static str { "TestingTestingTestingTestingTestingTesting" }
+        }
+
+        let oid: Oid<Long> = Oid::with_uuid(*oid().uuid());
+        assert_eq!(
+            serde_json::to_string(&oid).unwrap(),
+            format!("\"{oid}\"")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn oidstr_roundtrip(prefix in "[a-zA-Z0-9]{1,8}", uuid in any::<u128>()) {
+            let oid = OidStr::with_uuid(prefix.as_str(), uuid::Uuid::from_u128(uuid)).unwrap();
+            prop_assert_eq!(bincode(&oid).1, oid.clone());
+            prop_assert_eq!(postcard(&oid).1, oid.clone());
+            prop_assert_eq!(cbor(&oid).1, oid);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! (De)serializes an [`Oid`] as its UUID alone
+//!
+//! Human readable formats use the hyphenated UUID (e.g.
+//! `063dc3a0-3925-7c7f-8000-ca84a12ee183`) and other formats the 16 UUID
+//! bytes, i.e. the same as the `serde` impls of [`Uuid`].
+//!
+//! Only `Oid<P>` is supported, as the prefix of an `OidStr` would be lost.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use serde::{Deserialize, Serialize};
+//! # use typed_oid::{Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct User {
+//!     #[serde(with = "typed_oid::serde::as_uuid")]
+//!     id: Oid<Usr>,
+//! }
+//!
+//! let user = User {
+//!     id: "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap(),
+//! };
+//! let json = serde_json::to_string(&user).unwrap();
+//! assert_eq!(json, r#"{"id":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}"#);
+//! let parsed: User = serde_json::from_str(&json).unwrap();
+//! assert_eq!(parsed.id.uuid(), user.id.uuid());
+//! ```
+
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::{Oid, OidPrefix};
+
+/// Serializes the UUID of an [`Oid`]
+pub fn serialize<P, S>(oid: &Oid<P>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    P: OidPrefix,
+    S: Serializer,
+{
+    oid.uuid().serialize(serializer)
+}
+
+/// Deserializes an [`Oid`] from a UUID
+pub fn deserialize<'de, P, D>(deserializer: D) -> Result<Oid<P>, D::Error>
+where
+    P: OidPrefix,
+    D: Deserializer<'de>,
+{
+    Uuid::deserialize(deserializer).map(Oid::with_uuid)
+}
+
+#[cfg(test)]
+mod as_uuid_tests {
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::{Oid, OidPrefix};
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "super")]
+        id: Oid<Tst>,
+    }
+
+    fn wrapper() -> Wrapper {
+        Wrapper {
+            id: "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let json = serde_json::to_string(&wrapper()).unwrap();
+        assert_eq!(json, r#"{"id":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}"#);
+        let parsed: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.id.uuid(), wrapper().id.uuid());
+    }
+
+    #[test]
+    fn compact_roundtrip() {
+        let bytes = postcard::to_allocvec(&wrapper()).unwrap();
+        assert_eq!(bytes.len(), 17);
+        let parsed: Wrapper = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.id.uuid(), wrapper().id.uuid());
+    }
+
+    #[test]
+    fn rejects_toid() {
+        assert!(serde_json::from_str::<Wrapper>(r#"{"id":"Tst-0OUS781P4LU7V000PA2A2BN1GC"}"#).is_err());
+    }
+}
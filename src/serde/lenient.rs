@@ -0,0 +1,146 @@
+//! Deserializes an [`Oid`] from either a TOID or a bare UUID
+//!
+//! This is useful for data which still contains plain UUIDs from before TOIDs
+//! were introduced. Bare UUIDs may be in any format accepted by
+//! [`Uuid::try_parse`], or the 16 UUID bytes for formats which aren't human
+//! readable. Serialization is the same as the `Serialize` impl of [`Oid`].
+//!
+//! Only `Oid<P>` is supported, as the prefix of an `OidStr` can't be known
+//! from a bare UUID.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use serde::Deserialize;
+//! # use typed_oid::{Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     #[serde(with = "typed_oid::serde::lenient")]
+//!     id: Oid<Usr>,
+//! }
+//!
+//! let toid: User = serde_json::from_str(r#"{"id":"Usr-0OUS781P4LU7V000PA2A2BN1GC"}"#).unwrap();
+//! let uuid: User =
+//!     serde_json::from_str(r#"{"id":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}"#).unwrap();
+//! assert_eq!(toid.id.uuid(), uuid.id.uuid());
+//! ```
+
+use core::str::FromStr;
+
+use ::serde::{Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use super::{CompactVisitor, FromCompact, FromStrVisitor};
+use crate::{error::Error, Oid, OidPrefix};
+
+/// Serializes an [`Oid`] the same way as its `Serialize` impl
+pub fn serialize<P, S>(oid: &Oid<P>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    P: OidPrefix,
+    S: Serializer,
+{
+    oid.serialize(serializer)
+}
+
+/// Deserializes an [`Oid`] from either a TOID or a bare UUID
+pub fn deserialize<'de, P, D>(deserializer: D) -> Result<Oid<P>, D::Error>
+where
+    P: OidPrefix,
+    D: Deserializer<'de>,
+{
+    let lenient: Lenient<P> = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(FromStrVisitor::new())?
+    } else {
+        deserializer.deserialize_bytes(CompactVisitor::new())?
+    };
+    Ok(lenient.0)
+}
+
+/// An [`Oid`] parsed from either a TOID or a bare UUID
+struct Lenient<P>(Oid<P>);
+
+impl<P: OidPrefix> FromStr for Lenient<P> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(oid) => Ok(Self(oid)),
+            // Report why the TOID was invalid, rather than the UUID
+            Err(e) => Uuid::try_parse(s)
+                .map(|uuid| Self(Oid::with_uuid(uuid)))
+                .map_err(|_| e),
+        }
+    }
+}
+
+impl<P: OidPrefix> FromCompact for Lenient<P> {
+    fn from_compact(bytes: &[u8]) -> Option<Result<Self, Error>> {
+        Oid::from_compact(bytes).map(|res| res.map(Self))
+    }
+}
+
+#[cfg(test)]
+mod lenient_tests {
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::{Error, Oid, OidPrefix};
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "super")]
+        id: Oid<Tst>,
+    }
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+    const UUID: &str = "063dc3a0-3925-7c7f-8000-ca84a12ee183";
+
+    fn json(id: &str) -> serde_json::Result<Wrapper> {
+        serde_json::from_str(&format!(r#"{{"id":"{id}"}}"#))
+    }
+
+    #[test]
+    fn toid_or_uuid() {
+        let expected: Oid<Tst> = TOID.parse().unwrap();
+        for id in [TOID, UUID, "063dc3a039257c7f8000ca84a12ee183"] {
+            assert_eq!(json(id).unwrap().id.uuid(), expected.uuid(), "{id}");
+        }
+    }
+
+    #[test]
+    fn serializes_toid() {
+        let wrapper = json(UUID).unwrap();
+        assert_eq!(
+            serde_json::to_string(&wrapper).unwrap(),
+            format!(r#"{{"id":"{TOID}"}}"#)
+        );
+    }
+
+    #[test]
+    fn toid_error() {
+        let err = json("Other-0OUS781P4LU7V000PA2A2BN1GC").err().unwrap();
+        let expected = Error::UnexpectedPrefix {
+            expected: "Tst",
+            found: "Other".parse().unwrap(),
+        };
+        assert!(err.to_string().starts_with(&expected.to_string()));
+    }
+
+    #[test]
+    fn compact() {
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        for bytes in [
+            postcard::to_allocvec(&oid).unwrap(),
+            postcard::to_allocvec(TOID).unwrap(),
+            postcard::to_allocvec(UUID).unwrap(),
+        ] {
+            let wrapper: Wrapper = postcard::from_bytes(&bytes).unwrap();
+            assert_eq!(wrapper.id.uuid(), oid.uuid());
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! (De)serializes a TOID as a struct of its prefix and UUID
+//!
+//! In JSON this is
+//! `{"prefix":"Usr","uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}`. The UUID
+//! uses the `serde` impls of [`Uuid`](uuid::Uuid), i.e. the hyphenated UUID
+//! for human readable formats and the 16 UUID bytes otherwise.
+//!
+//! Both [`Oid`](crate::Oid) and [`OidStr`](crate::OidStr) are supported. When
+//! deserializing an `Oid<P>` the prefix must match `P`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use serde::{Deserialize, Serialize};
+//! # use typed_oid::{Oid, OidPrefix, OidStr};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct User {
+//!     #[serde(with = "typed_oid::serde::structured")]
+//!     id: Oid<Usr>,
+//!     #[serde(with = "typed_oid::serde::structured")]
+//!     parent: OidStr,
+//! }
+//!
+//! let user = User {
+//!     id: "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap(),
+//!     parent: "Org-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap(),
+//! };
+//! let json = serde_json::to_string(&user).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"id":{"prefix":"Usr","uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183"},"parent":{"prefix":"Org","uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}}"#
+//! );
+//! let parsed: User = serde_json::from_str(&json).unwrap();
+//! assert_eq!(parsed.parent, user.parent);
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use ::serde::{
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serializer,
+};
+
+const NAME: &str = "Toid";
+const FIELDS: &[&str] = &["prefix", "uuid"];
+
+/// A TOID which can be (de)serialized with this module, i.e.
+/// [`Oid`](crate::Oid) or [`OidStr`](crate::OidStr)
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait Structured: private::Sealed {}
+
+pub(crate) mod private {
+    use uuid::Uuid;
+
+    pub trait Sealed: Sized {
+        /// A validated prefix, held until the UUID has been deserialized
+        type Prefix;
+
+        fn prefix_str(&self) -> &str;
+
+        fn uuid(&self) -> &Uuid;
+
+        fn parse_prefix(prefix: &str) -> crate::Result<Self::Prefix>;
+
+        fn from_parts(prefix: Self::Prefix, uuid: Uuid) -> Self;
+    }
+}
+
+/// Serializes a TOID as a struct of its prefix and UUID
+pub fn serialize<T, S>(oid: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Structured,
+    S: Serializer,
+{
+    let mut state = serializer.serialize_struct(NAME, FIELDS.len())?;
+    state.serialize_field("prefix", oid.prefix_str())?;
+    state.serialize_field("uuid", oid.uuid())?;
+    state.end()
+}
+
+/// Deserializes a TOID from a struct of its prefix and UUID
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Structured,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct(NAME, FIELDS, StructuredVisitor(PhantomData))
+}
+
+struct StructuredVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Structured> Visitor<'de> for StructuredVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a TOID struct of a prefix and a UUID")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let prefix = seq
+            .next_element_seed(PrefixSeed::<T>(PhantomData))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let uuid = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(T::from_parts(prefix, uuid))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut prefix = None;
+        let mut uuid = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Prefix if prefix.is_some() => {
+                    return Err(de::Error::duplicate_field("prefix"));
+                }
+                Field::Prefix => prefix = Some(map.next_value_seed(PrefixSeed::<T>(PhantomData))?),
+                Field::Uuid if uuid.is_some() => return Err(de::Error::duplicate_field("uuid")),
+                Field::Uuid => uuid = Some(map.next_value()?),
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let prefix = prefix.ok_or_else(|| de::Error::missing_field("prefix"))?;
+        let uuid = uuid.ok_or_else(|| de::Error::missing_field("uuid"))?;
+        Ok(T::from_parts(prefix, uuid))
+    }
+}
+
+/// Validates the prefix field as soon as it's deserialized, so it doesn't need
+/// to be kept as a (possibly allocated) string
+struct PrefixSeed<T>(PhantomData<T>);
+
+impl<'de, T: Structured> DeserializeSeed<'de> for PrefixSeed<T> {
+    type Value = T::Prefix;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T::Prefix, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, T: Structured> Visitor<'de> for PrefixSeed<T> {
+    type Value = T::Prefix;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a TOID prefix") }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T::Prefix, E> {
+        T::parse_prefix(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T::Prefix, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+enum Field {
+    Prefix,
+    Uuid,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("a field name") }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+        Ok(match v {
+            0 => Field::Prefix,
+            1 => Field::Uuid,
+            _ => Field::Other,
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> { self.visit_bytes(v.as_bytes()) }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field, E> {
+        Ok(match v {
+            b"prefix" => Field::Prefix,
+            b"uuid" => Field::Uuid,
+            _ => Field::Other,
+        })
+    }
+}
+
+#[cfg(test)]
+mod structured_tests {
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::{Oid, OidPrefix, OidStr};
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "super")]
+        oid: Oid<Tst>,
+        #[serde(with = "super")]
+        oidstr: OidStr,
+    }
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+    const JSON: &str = r#"{"oid":{"prefix":"Tst","uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183"},"oidstr":{"prefix":"Tst","uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183"}}"#;
+
+    fn wrapper() -> Wrapper {
+        Wrapper {
+            oid: TOID.parse().unwrap(),
+            oidstr: TOID.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        assert_eq!(serde_json::to_string(&wrapper()).unwrap(), JSON);
+        assert_eq!(serde_json::from_str::<Wrapper>(JSON).unwrap(), wrapper());
+    }
+
+    #[test]
+    fn compact_roundtrip() {
+        let bytes = postcard::to_allocvec(&wrapper()).unwrap();
+        assert_eq!(postcard::from_bytes::<Wrapper>(&bytes).unwrap(), wrapper());
+    }
+
+    #[test]
+    fn field_order_and_unknown_fields() {
+        let json = r#"{"oid":{"uuid":"063dc3a0-3925-7c7f-8000-ca84a12ee183","extra":[1],"prefix":"Tst"},"oidstr":["Tst","063dc3a0-3925-7c7f-8000-ca84a12ee183"]}"#;
+        assert_eq!(serde_json::from_str::<Wrapper>(json).unwrap(), wrapper());
+    }
+
+    #[test]
+    fn unexpected_prefix() {
+        let json = JSON.replacen("Tst", "Other", 1);
+        let err = serde_json::from_str::<Wrapper>(&json).unwrap_err();
+        assert!(err.to_string().starts_with("expected prefix `Tst` but found `Other`"));
+    }
+
+    #[test]
+    fn missing_and_duplicate_fields() {
+        let deserialize = |json| {
+            super::deserialize::<OidStr, _>(&mut serde_json::Deserializer::from_str(json))
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(deserialize(r#"{"prefix":"Tst"}"#).starts_with("missing field `uuid`"));
+        assert!(deserialize(r#"{"prefix":"Tst","prefix":"Tst"}"#)
+            .starts_with("duplicate field `prefix`"));
+    }
+}
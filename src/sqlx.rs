@@ -0,0 +1,196 @@
+//! [`sqlx`](::sqlx) support for TOIDs
+//!
+//! An [`Oid`] is stored as its UUID, i.e. using the driver's UUID mapping
+//! (native `uuid` on Postgres, `BINARY(16)` on MySQL and a 16 byte BLOB on
+//! SQLite). Values of the driver's text types (e.g. `TEXT`, `VARCHAR` or
+//! `BPCHAR` on Postgres) holding a TOID string can be decoded as well, which
+//! on SQLite allows either a BLOB or TEXT column. An [`OidStr`] is stored as
+//! TOID text, as its prefix isn't part of the type.
+
+use alloc::string::{String, ToString};
+
+use ::sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type, ValueRef};
+use uuid::Uuid;
+
+use crate::{Oid, OidPrefix, OidStr};
+
+impl<P, DB> Type<DB> for Oid<P>
+where
+    P: OidPrefix,
+    DB: Database,
+    Uuid: Type<DB>,
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo { <Uuid as Type<DB>>::type_info() }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <Uuid as Type<DB>>::compatible(ty) || <str as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, P, DB> Encode<'q, DB> for Oid<P>
+where
+    P: OidPrefix,
+    DB: Database,
+    Uuid: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.uuid().encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> { self.uuid().produces() }
+
+    fn size_hint(&self) -> usize { self.uuid().size_hint() }
+}
+
+impl<'r, P, DB> Decode<'r, DB> for Oid<P>
+where
+    P: OidPrefix,
+    DB: Database,
+    Uuid: Decode<'r, DB>,
+    &'r str: Decode<'r, DB>,
+    str: Type<DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        if is_text::<DB>(&value.type_info()) {
+            return Ok(<&str as Decode<DB>>::decode(value)?.parse()?);
+        }
+        Ok(Self::with_uuid(Uuid::decode(value)?))
+    }
+}
+
+/// Whether a value of `ty` holds a TOID string rather than a UUID
+///
+/// None of the drivers' UUID column types are compatible with strings (e.g.
+/// `BINARY(16)` on MySQL is excluded by its binary flag), so any type the
+/// driver decodes as a string (e.g. `VARCHAR` and `BPCHAR` on Postgres) is
+/// parsed as a TOID.
+fn is_text<DB>(ty: &DB::TypeInfo) -> bool
+where
+    DB: Database,
+    str: Type<DB>,
+{
+    <&str as Type<DB>>::compatible(ty)
+}
+
+impl<DB> Type<DB> for OidStr
+where
+    DB: Database,
+    str: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo { <str as Type<DB>>::type_info() }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool { <str as Type<DB>>::compatible(ty) }
+}
+
+impl<'q, DB> Encode<'q, DB> for OidStr
+where
+    DB: Database,
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.to_string().encode(buf)
+    }
+}
+
+impl<'r, DB> Decode<'r, DB> for OidStr
+where
+    DB: Database,
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<DB>>::decode(value)?.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod sqlx_tests {
+    use ::sqlx::{Connection, Row, SqliteConnection};
+
+    use super::*;
+    use crate::Error;
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+
+    async fn connect() -> SqliteConnection {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        ::sqlx::query("CREATE TABLE t (b BLOB, s TEXT)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn
+    }
+
+    #[tokio::test]
+    async fn oid_blob_roundtrip() {
+        let mut conn = connect().await;
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        ::sqlx::query("INSERT INTO t (b) VALUES (?)")
+            .bind(oid)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let row = ::sqlx::query("SELECT b FROM t").fetch_one(&mut conn).await.unwrap();
+        assert_eq!(row.get::<Vec<u8>, _>(0), oid.uuid().as_bytes());
+        assert_eq!(row.get::<Oid<Tst>, _>(0), oid);
+    }
+
+    #[tokio::test]
+    async fn oid_from_text() {
+        let mut conn = connect().await;
+        ::sqlx::query("INSERT INTO t (s) VALUES (?), ('Other-0OUS781P4LU7V000PA2A2BN1GC')")
+            .bind(TOID)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let rows = ::sqlx::query("SELECT s FROM t").fetch_all(&mut conn).await.unwrap();
+        assert_eq!(rows[0].get::<Oid<Tst>, _>(0), TOID.parse().unwrap());
+        let err = rows[1].try_get::<Oid<Tst>, _>(0).unwrap_err();
+        let source = match err {
+            ::sqlx::Error::ColumnDecode { source, .. } => source,
+            err => panic!("unexpected error {err:?}"),
+        };
+        assert_eq!(
+            source.downcast_ref::<Error>(),
+            Some(&Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Other".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn postgres_text_types() {
+        use ::sqlx::{postgres::PgTypeInfo, Postgres};
+
+        for name in ["text", "varchar", "bpchar", "citext"] {
+            let ty = PgTypeInfo::with_name(name);
+            assert!(is_text::<Postgres>(&ty), "{name}");
+            assert!(<Oid<Tst> as Type<Postgres>>::compatible(&ty), "{name}");
+        }
+        let uuid = PgTypeInfo::with_name("uuid");
+        assert!(!is_text::<Postgres>(&uuid));
+        assert!(<Oid<Tst> as Type<Postgres>>::compatible(&uuid));
+    }
+
+    #[tokio::test]
+    async fn oidstr_text_roundtrip() {
+        let mut conn = connect().await;
+        let oid: OidStr = TOID.parse().unwrap();
+        ::sqlx::query("INSERT INTO t (s) VALUES (?)")
+            .bind(&oid)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let row = ::sqlx::query("SELECT s FROM t").fetch_one(&mut conn).await.unwrap();
+        assert_eq!(row.get::<&str, _>(0), TOID);
+        assert_eq!(row.get::<OidStr, _>(0), oid);
+    }
+}
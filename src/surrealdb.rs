@@ -0,0 +1,357 @@
+//! [SurrealDB](https://surrealdb.com) support for TOIDs
+//!
+//! A TOID maps to a record ID whose table is the prefix of the TOID. The
+//! record key is either a native UUID (the default) or the encoded value of
+//! the TOID as a string, selected with [`KeyFormat`]. The `surrealdb` feature
+//! supports the [`Thing`] of SurrealDB 1.x, and the `surrealdb2` feature the
+//! [`RecordId`] of the SurrealDB 2.x client. SurrealDB 1.x has no native UUID
+//! keys, so a [`Thing`] holds the UUID as a hyphenated string instead.
+//!
+//! When converting back, keys holding a native UUID, a hyphenated UUID string
+//! or an encoded value are all accepted. Converting into an [`Oid`] requires
+//! the table to match its prefix.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(feature = "surrealdb")] {
+//! # use surrealdb::sql::{Id, Thing};
+//! # use typed_oid::{surrealdb::KeyFormat, Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! let oid: Oid<Usr> = "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+//!
+//! let thing = Thing::from(oid);
+//! assert_eq!(thing.tb, "Usr");
+//! assert_eq!(
+//!     thing.id,
+//!     Id::String("063dc3a0-3925-7c7f-8000-ca84a12ee183".into())
+//! );
+//!
+//! let thing = oid.to_thing(KeyFormat::Value);
+//! assert_eq!(thing.id, Id::String("0OUS781P4LU7V000PA2A2BN1GC".into()));
+//! assert_eq!(Oid::<Usr>::try_from(thing).unwrap().uuid(), oid.uuid());
+//! # }
+//! ```
+//!
+//! ```rust
+//! # #[cfg(feature = "surrealdb2")] {
+//! # use surrealdb2 as surrealdb;
+//! # use surrealdb::RecordId;
+//! # use typed_oid::{Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! let oid: Oid<Usr> = "Usr-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+//!
+//! let record = RecordId::from(oid);
+//! assert_eq!(record.table(), "Usr");
+//! assert_eq!(Oid::<Usr>::try_from(record).unwrap().uuid(), oid.uuid());
+//! # }
+//! ```
+//!
+//! [`Thing`]: https://docs.rs/surrealdb/1/surrealdb/sql/struct.Thing.html
+//! [`RecordId`]: https://docs.rs/surrealdb/2/surrealdb/struct.RecordId.html
+
+#[cfg(feature = "surrealdb")]
+use ::surrealdb::sql::{Id, Thing};
+#[cfg(feature = "surrealdb2")]
+use ::surrealdb2::{RecordId, RecordIdKey};
+use uuid::Uuid;
+
+use crate::{
+    encoding::{decode_value, Base32Hex, OidEncoding},
+    error::{Error, Result},
+    oid::{prefix_matches, unexpected_prefix},
+    Oid, OidPrefix, OidStr,
+};
+
+/// The form of the key of a record ID created from a TOID
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyFormat {
+    /// A native UUID key, e.g. `Usr:u'063dc3a0-3925-7c7f-8000-ca84a12ee183'`,
+    /// or a hyphenated UUID string key for a SurrealDB 1.x `Thing`
+    #[default]
+    Uuid,
+    /// A string key of the encoded value, e.g. `Usr:0OUS781P4LU7V000PA2A2BN1GC`
+    Value,
+}
+
+impl<P: OidPrefix> Oid<P> {
+    /// Convert the TOID into a SurrealDB [`Thing`] of the table `P::prefix()`
+    /// with a key in the given [`KeyFormat`]
+    #[cfg(feature = "surrealdb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "surrealdb")))]
+    pub fn to_thing(&self, format: KeyFormat) -> Thing {
+        Thing::from((
+            self.prefix_str(),
+            thing_id(self.uuid(), format, || self.value()),
+        ))
+    }
+
+    /// Convert the TOID into a SurrealDB [`RecordId`] of the table
+    /// `P::prefix()` with a key in the given [`KeyFormat`]
+    #[cfg(feature = "surrealdb2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "surrealdb2")))]
+    pub fn to_record_id(&self, format: KeyFormat) -> RecordId {
+        RecordId::from_table_key(
+            self.prefix_str(),
+            record_key(self.uuid(), format, || self.value()),
+        )
+    }
+}
+
+impl OidStr {
+    /// Convert the OID into a SurrealDB [`Thing`] whose table is the prefix
+    /// with a key in the given [`KeyFormat`]
+    #[cfg(feature = "surrealdb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "surrealdb")))]
+    pub fn to_thing(&self, format: KeyFormat) -> Thing {
+        Thing::from((
+            self.prefix().as_str(),
+            thing_id(self.uuid(), format, || self.value()),
+        ))
+    }
+
+    /// Convert the OID into a SurrealDB [`RecordId`] whose table is the prefix
+    /// with a key in the given [`KeyFormat`]
+    #[cfg(feature = "surrealdb2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "surrealdb2")))]
+    pub fn to_record_id(&self, format: KeyFormat) -> RecordId {
+        RecordId::from_table_key(
+            self.prefix().as_str(),
+            record_key(self.uuid(), format, || self.value()),
+        )
+    }
+}
+
+/// Reads the UUID from a string key of either a hyphenated UUID or a value in
+/// `encoding`
+fn str_key_uuid(s: &str, encoding: &dyn OidEncoding) -> Result<Uuid> {
+    Uuid::try_parse(s).or_else(|_| decode_value(encoding, s))
+}
+
+#[cfg(feature = "surrealdb")]
+fn thing_id(uuid: &Uuid, format: KeyFormat, value: impl FnOnce() -> String) -> Id {
+    match format {
+        KeyFormat::Uuid => Id::String(uuid.hyphenated().to_string()),
+        KeyFormat::Value => Id::String(value()),
+    }
+}
+
+/// Reads the UUID from the key of a [`Thing`]
+#[cfg(feature = "surrealdb")]
+fn thing_id_uuid(id: &Id, encoding: &dyn OidEncoding) -> Result<Uuid> {
+    match id {
+        Id::String(s) => str_key_uuid(s, encoding),
+        _ => Err(Error::InvalidRecordKey),
+    }
+}
+
+#[cfg(feature = "surrealdb2")]
+fn record_key(uuid: &Uuid, format: KeyFormat, value: impl FnOnce() -> String) -> RecordIdKey {
+    match format {
+        KeyFormat::Uuid => RecordIdKey::from(*uuid),
+        KeyFormat::Value => RecordIdKey::from(value()),
+    }
+}
+
+/// Reads the UUID from the key of a [`RecordId`]
+#[cfg(feature = "surrealdb2")]
+fn record_key_uuid(key: &RecordIdKey, encoding: &dyn OidEncoding) -> Result<Uuid> {
+    if let Ok(uuid) = Uuid::try_from(key.clone()) {
+        return Ok(uuid);
+    }
+    match String::try_from(key.clone()) {
+        Ok(s) => str_key_uuid(&s, encoding),
+        Err(_) => Err(Error::InvalidRecordKey),
+    }
+}
+
+#[cfg(feature = "surrealdb")]
+impl<P: OidPrefix> From<Oid<P>> for Thing {
+    fn from(oid: Oid<P>) -> Self { oid.to_thing(KeyFormat::Uuid) }
+}
+
+#[cfg(feature = "surrealdb")]
+impl<P: OidPrefix> TryFrom<Thing> for Oid<P> {
+    type Error = Error;
+
+    fn try_from(thing: Thing) -> Result<Self> {
+        if !prefix_matches::<P>(&thing.tb) {
+            return Err(unexpected_prefix::<P>(&thing.tb));
+        }
+        thing_id_uuid(&thing.id, P::encoding()).map(Self::with_uuid)
+    }
+}
+
+#[cfg(feature = "surrealdb2")]
+impl<P: OidPrefix> From<Oid<P>> for RecordId {
+    fn from(oid: Oid<P>) -> Self { oid.to_record_id(KeyFormat::Uuid) }
+}
+
+#[cfg(feature = "surrealdb2")]
+impl<P: OidPrefix> TryFrom<RecordId> for Oid<P> {
+    type Error = Error;
+
+    fn try_from(record: RecordId) -> Result<Self> {
+        if !prefix_matches::<P>(record.table()) {
+            return Err(unexpected_prefix::<P>(record.table()));
+        }
+        record_key_uuid(record.key(), P::encoding()).map(Self::with_uuid)
+    }
+}
+
+#[cfg(feature = "surrealdb")]
+impl From<OidStr> for Thing {
+    fn from(oid: OidStr) -> Self { oid.to_thing(KeyFormat::Uuid) }
+}
+
+#[cfg(feature = "surrealdb")]
+impl TryFrom<Thing> for OidStr {
+    type Error = Error;
+
+    fn try_from(thing: Thing) -> Result<Self> {
+        let uuid = thing_id_uuid(&thing.id, &Base32Hex)?;
+        Self::with_uuid(thing.tb.as_str(), uuid)
+    }
+}
+
+#[cfg(feature = "surrealdb2")]
+impl From<OidStr> for RecordId {
+    fn from(oid: OidStr) -> Self { oid.to_record_id(KeyFormat::Uuid) }
+}
+
+#[cfg(feature = "surrealdb2")]
+impl TryFrom<RecordId> for OidStr {
+    type Error = Error;
+
+    fn try_from(record: RecordId) -> Result<Self> {
+        let uuid = record_key_uuid(record.key(), &Base32Hex)?;
+        Self::with_uuid(record.table(), uuid)
+    }
+}
+
+#[cfg(test)]
+mod surrealdb_tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[derive(Debug, PartialEq)]
+    struct Test;
+    impl OidPrefix for Test {
+        fn str_partial_eq(s: &str) -> bool { "test" == s }
+    }
+
+    const TOID: &str = "Tst-0OUS781P4LU7V000PA2A2BN1GC";
+    const UUID: &str = "063dc3a0-3925-7c7f-8000-ca84a12ee183";
+    const VALUE: &str = "0OUS781P4LU7V000PA2A2BN1GC";
+
+    #[cfg(feature = "surrealdb")]
+    fn thing(tb: &str, id: Id) -> Thing { Thing::from((tb, id)) }
+
+    #[test]
+    #[cfg(feature = "surrealdb")]
+    fn oid_to_thing() {
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        let uuid = Id::String(UUID.into());
+        assert_eq!(Thing::from(oid), thing("Tst", uuid.clone()));
+        assert_eq!(oid.to_thing(KeyFormat::Uuid), thing("Tst", uuid));
+        assert_eq!(
+            oid.to_thing(KeyFormat::Value),
+            thing("Tst", Id::String(VALUE.into()))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb")]
+    fn oid_from_thing() {
+        let oid: Oid<Test> = "Test-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        for id in [Id::String(UUID.into()), Id::String(VALUE.into())] {
+            assert_eq!(Oid::<Test>::try_from(thing("test", id)).unwrap(), oid);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb")]
+    fn prefix_mismatch() {
+        for tb in ["Ts", "Tstx", "Frm"] {
+            let toid: Result<Oid<Tst>> = thing(tb, Id::String(VALUE.into())).try_into();
+            assert_eq!(
+                toid.unwrap_err(),
+                Error::UnexpectedPrefix {
+                    expected: "Tst",
+                    found: tb.parse().unwrap()
+                }
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb")]
+    fn invalid_key() {
+        let toid: Result<Oid<Tst>> = thing("Tst", Id::Number(1)).try_into();
+        assert_eq!(toid.unwrap_err(), Error::InvalidRecordKey);
+        let toid: Result<Oid<Tst>> = thing("Tst", Id::String("nope".into())).try_into();
+        assert!(toid.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb")]
+    fn oidstr_roundtrip() {
+        let oid: OidStr = TOID.parse().unwrap();
+        assert_eq!(OidStr::try_from(Thing::from(oid.clone())).unwrap(), oid);
+        assert_eq!(
+            OidStr::try_from(oid.to_thing(KeyFormat::Value)).unwrap(),
+            oid
+        );
+        assert_eq!(
+            OidStr::try_from(thing("Tst-x", Id::String(VALUE.into()))).unwrap_err(),
+            Error::InvalidPrefix { valid_until: 3 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb2")]
+    fn record_id_roundtrip() {
+        let oid: Oid<Tst> = TOID.parse().unwrap();
+        let record = RecordId::from(oid);
+        assert_eq!(record.table(), "Tst");
+        assert_eq!(record.to_string(), format!("Tst:u'{UUID}'"));
+        assert_eq!(Oid::<Tst>::try_from(record).unwrap(), oid);
+
+        let oid: OidStr = TOID.parse().unwrap();
+        let record = oid.to_record_id(KeyFormat::Value);
+        assert_eq!(record.to_string(), format!("Tst:{VALUE}"));
+        assert_eq!(OidStr::try_from(record).unwrap(), oid);
+    }
+
+    #[test]
+    #[cfg(feature = "surrealdb2")]
+    fn oid_from_record_id() {
+        let oid: Oid<Test> = "Test-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        for key in [
+            RecordIdKey::from(*oid.uuid()),
+            RecordIdKey::from(UUID),
+            RecordIdKey::from(VALUE),
+        ] {
+            let record = RecordId::from_table_key("test", key);
+            assert_eq!(Oid::<Test>::try_from(record).unwrap(), oid);
+        }
+
+        let toid: Result<Oid<Tst>> = RecordId::from_table_key("Tst", 1).try_into();
+        assert_eq!(toid.unwrap_err(), Error::InvalidRecordKey);
+        let toid: Result<Oid<Tst>> = RecordId::from_table_key("Frm", VALUE).try_into();
+        assert_eq!(
+            toid.unwrap_err(),
+            Error::UnexpectedPrefix {
+                expected: "Tst",
+                found: "Frm".parse().unwrap()
+            }
+        );
+    }
+}
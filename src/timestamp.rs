@@ -0,0 +1,232 @@
+//! Conversions between the timestamps of time-based UUIDs and date-time types
+//!
+//! The creation time of a TOID with a time-based UUID (version 1, 6 or 7) can
+//! be read with `created_at` into any [`UnixTimestamp`], and a TOID with a
+//! UUIDv7 can be created for a given time with `new_v7_at`. Implementations
+//! are provided for [`SystemTime`](std::time::SystemTime) with the `std`
+//! feature, and for the date-time types of the `chrono`, `time` and `jiff`
+//! features.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(all(feature = "uuid_v7", feature = "std"))] {
+//! # use std::time::{Duration, SystemTime};
+//! # use typed_oid::{Oid, OidPrefix};
+//! struct Usr;
+//! impl OidPrefix for Usr {}
+//!
+//! let at = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+//! let oid: Oid<Usr> = Oid::new_v7_at(at);
+//! assert_eq!(oid.timestamp(), Some(at));
+//! # }
+//! ```
+
+use uuid::{Uuid, Version};
+
+/// A point in time which can be converted to and from a UNIX timestamp
+pub trait UnixTimestamp: Sized {
+    /// Create from the seconds and subsecond nanoseconds since the UNIX epoch,
+    /// returning `None` if the time can't be represented
+    fn from_unix(secs: u64, nanos: u32) -> Option<Self>;
+
+    /// The seconds and subsecond nanoseconds since the UNIX epoch, where times
+    /// before the epoch saturate to the epoch
+    fn to_unix(&self) -> (u64, u32);
+}
+
+/// The largest number of seconds since the UNIX epoch which fits in the 60 bit
+/// timestamp of a version 1 or 6 UUID
+const MAX_GREGORIAN_SECS: u64 = (1 << 60) / 10_000_000;
+
+/// Reads the timestamp of a time-based UUID as seconds and subsecond
+/// nanoseconds since the UNIX epoch, which is `None` for other versions or a
+/// version 1 or 6 timestamp before the epoch
+pub(crate) fn uuid_unix_time(uuid: &Uuid) -> Option<(u64, u32)> {
+    let (secs, nanos) = uuid.get_timestamp()?.to_unix();
+    // Timestamps of version 1 and 6 UUIDs before the UNIX epoch wrap around
+    if matches!(uuid.get_version(), Some(Version::Mac | Version::SortMac))
+        && secs > MAX_GREGORIAN_SECS
+    {
+        return None;
+    }
+    Some((secs, nanos))
+}
+
+/// Creates a UUIDv7 for the given time
+#[cfg(feature = "uuid_v7")]
+pub(crate) fn uuid_v7_at<T: UnixTimestamp>(t: &T) -> Uuid {
+    let (secs, nanos) = t.to_unix();
+    Uuid::new_v7(uuid::Timestamp::from_unix(uuid::NoContext, secs, nanos))
+}
+
+/// The largest millisecond timestamp of a UUIDv7
+const MAX_V7_MILLIS: u64 = (1 << 48) - 1;
+/// The version and variant bits of a UUIDv7 with all random bits unset
+const V7_MIN_RAND: u128 = 0x7000_8000_0000_0000_0000;
+/// The version and variant bits of a UUIDv7 with all random bits set
+const V7_MAX_RAND: u128 = 0x7fff_bfff_ffff_ffff_ffff;
+
+/// The smallest and largest possible UUIDv7 for the millisecond of the given
+/// time, where times after the largest UUIDv7 timestamp saturate to it
+pub(crate) fn uuid_v7_bounds<T: UnixTimestamp>(t: &T) -> (Uuid, Uuid) {
+    let (secs, nanos) = t.to_unix();
+    let millis = secs
+        .saturating_mul(1_000)
+        .saturating_add(u64::from(nanos / 1_000_000))
+        .min(MAX_V7_MILLIS);
+    let ts = u128::from(millis) << 80;
+    (
+        Uuid::from_u128(ts | V7_MIN_RAND),
+        Uuid::from_u128(ts | V7_MAX_RAND),
+    )
+}
+
+#[cfg(feature = "std")]
+impl UnixTimestamp for std::time::SystemTime {
+    fn from_unix(secs: u64, nanos: u32) -> Option<Self> {
+        Self::UNIX_EPOCH.checked_add(std::time::Duration::new(secs, nanos))
+    }
+
+    fn to_unix(&self) -> (u64, u32) {
+        self.duration_since(Self::UNIX_EPOCH)
+            .map(|d| (d.as_secs(), d.subsec_nanos()))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl UnixTimestamp for ::chrono::DateTime<::chrono::Utc> {
+    fn from_unix(secs: u64, nanos: u32) -> Option<Self> {
+        Self::from_timestamp(secs.try_into().ok()?, nanos)
+    }
+
+    fn to_unix(&self) -> (u64, u32) {
+        match u64::try_from(self.timestamp()) {
+            Ok(secs) => (secs, self.timestamp_subsec_nanos()),
+            Err(_) => (0, 0),
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl UnixTimestamp for ::time::OffsetDateTime {
+    fn from_unix(secs: u64, nanos: u32) -> Option<Self> {
+        Self::from_unix_timestamp(secs.try_into().ok()?)
+            .ok()?
+            .replace_nanosecond(nanos)
+            .ok()
+    }
+
+    fn to_unix(&self) -> (u64, u32) {
+        match u64::try_from(self.unix_timestamp()) {
+            Ok(secs) => (secs, self.nanosecond()),
+            Err(_) => (0, 0),
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl UnixTimestamp for ::jiff::Timestamp {
+    fn from_unix(secs: u64, nanos: u32) -> Option<Self> {
+        Self::new(secs.try_into().ok()?, nanos.try_into().ok()?).ok()
+    }
+
+    fn to_unix(&self) -> (u64, u32) {
+        match u64::try_from(self.as_second()) {
+            // Non-negative timestamps have non-negative subsecond nanoseconds
+            Ok(secs) => (secs, self.subsec_nanosecond() as u32),
+            Err(_) => (0, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn v7() {
+        let uuid = Uuid::parse_str("063dc3a0-3925-7c7f-8000-ca84a12ee183").unwrap();
+        assert_eq!(uuid_unix_time(&uuid), Some((6_862_344_829, 221_000_000)));
+    }
+
+    #[test]
+    fn v1_and_v6() {
+        // 2023-11-14T22:13:20.1234567Z
+        let v1 = Uuid::parse_str("04c29687-833b-11ee-8000-000000000000").unwrap();
+        let v6 = Uuid::parse_str("1ee833b0-4c29-6687-8000-000000000000").unwrap();
+        assert_eq!(uuid_unix_time(&v1), Some((1_700_000_000, 123_456_700)));
+        assert_eq!(uuid_unix_time(&v6), Some((1_700_000_000, 123_456_700)));
+
+        // Before the UNIX epoch
+        let v1 = Uuid::parse_str("00000000-0000-1000-8000-000000000000").unwrap();
+        assert_eq!(uuid_unix_time(&v1), None);
+    }
+
+    #[test]
+    fn not_time_based() {
+        let v4 = Uuid::parse_str("b3cfdafa-3fec-41e2-82bf-ff881131abf1").unwrap();
+        assert_eq!(uuid_unix_time(&v4), None);
+        assert_eq!(uuid_unix_time(&Uuid::nil()), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn v7_bounds() {
+        use std::time::{Duration, SystemTime};
+
+        let at = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let (min, max) = uuid_v7_bounds(&at);
+        assert_eq!(min.to_string(), "018bcfe5-687b-7000-8000-000000000000");
+        assert_eq!(max.to_string(), "018bcfe5-687b-7fff-bfff-ffffffffffff");
+        assert_eq!(min.get_version(), Some(Version::SortRand));
+        assert_eq!(max.get_version(), Some(Version::SortRand));
+        assert_eq!(uuid_unix_time(&min), Some((1_700_000_000, 123_000_000)));
+
+        let (min, max) = uuid_v7_bounds(&SystemTime::UNIX_EPOCH);
+        assert_eq!(min.to_string(), "00000000-0000-7000-8000-000000000000");
+        assert_eq!(max.to_string(), "00000000-0000-7fff-bfff-ffffffffffff");
+
+        let end = SystemTime::UNIX_EPOCH + Duration::from_secs(u64::MAX / 1_000);
+        let (min, max) = uuid_v7_bounds(&end);
+        assert_eq!(min.to_string(), "ffffffff-ffff-7000-8000-000000000000");
+        assert_eq!(max.to_string(), "ffffffff-ffff-7fff-bfff-ffffffffffff");
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn chrono() {
+        use ::chrono::{DateTime, Utc};
+
+        let t = DateTime::<Utc>::from_unix(1_700_000_000, 123_000_000).unwrap();
+        assert_eq!(t.timestamp_millis(), 1_700_000_000_123);
+        assert_eq!(t.to_unix(), (1_700_000_000, 123_000_000));
+        assert_eq!(DateTime::<Utc>::from_timestamp(-1, 0).unwrap().to_unix(), (0, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time() {
+        use ::time::OffsetDateTime;
+
+        let t = OffsetDateTime::from_unix(1_700_000_000, 123_000_000).unwrap();
+        assert_eq!(t.unix_timestamp_nanos(), 1_700_000_000_123_000_000);
+        assert_eq!(t.to_unix(), (1_700_000_000, 123_000_000));
+        assert_eq!(OffsetDateTime::from_unix(u64::MAX, 0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "jiff")]
+    fn jiff() {
+        use ::jiff::Timestamp;
+
+        let t = Timestamp::from_unix(1_700_000_000, 123_000_000).unwrap();
+        assert_eq!(t.to_string(), "2023-11-14T22:13:20.123Z");
+        assert_eq!(t.to_unix(), (1_700_000_000, 123_000_000));
+        assert_eq!(Timestamp::from_unix(u64::MAX, 0), None);
+    }
+}
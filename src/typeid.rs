@@ -0,0 +1,245 @@
+//! Conversions between TOIDs and [TypeIDs](https://github.com/jetify-com/typeid)
+//!
+//! A TypeID (`prefix_01h455vb4pex5vsknk084sn02q`) is a lower case prefix and a
+//! UUID encoded with lower case [`Crockford`] base32, joined by `_`. Not every
+//! TOID has an equivalent TypeID and vice versa:
+//!
+//! - TypeID prefixes may only contain `a-z`, so TOID prefixes containing digits
+//!   can't be converted. Upper case letters of a TOID prefix are converted to
+//!   lower case.
+//! - TOID prefixes can't be empty or contain `_`, so TypeIDs without a prefix
+//!   or with a `_` inside the prefix can't be converted.
+
+use alloc::string::String;
+
+use uuid::Uuid;
+
+use crate::{
+    encoding::{Crockford, MAX_VALUE_LEN, OidEncoding},
+    error::{Error, Result},
+    format::Case,
+};
+
+/// The maximum length of a TypeID prefix
+const MAX_PREFIX_LEN: usize = 63;
+
+/// Builds the TypeID for a TOID with the given prefix and UUID
+pub(crate) fn to_typeid(prefix: &str, uuid: &Uuid) -> Result<String> {
+    check_prefix(prefix, u8::is_ascii_alphabetic)?;
+
+    let mut buf = [0; MAX_VALUE_LEN];
+    let value = Crockford.encode(uuid, Case::Lower, &mut buf);
+    let mut typeid = String::with_capacity(prefix.len() + 1 + value.len());
+    typeid.extend(prefix.chars().map(|c| c.to_ascii_lowercase()));
+    typeid.push('_');
+    typeid.push_str(value);
+    Ok(typeid)
+}
+
+/// Splits a TypeID into its prefix and decoded UUID
+///
+/// Any indexes in returned errors are relative to the start of `s`
+pub(crate) fn parse_typeid(s: &str) -> Result<(&str, Uuid)> {
+    // TypeID prefixes may themselves contain `_`, so the separator is the last.
+    // Without a separator the TypeID has an empty prefix, which a TOID can't
+    // have
+    let (pfx, val) = s.rsplit_once('_').ok_or(Error::MissingPrefix)?;
+    if pfx.is_empty() {
+        return Err(Error::MissingPrefix);
+    }
+    check_prefix(pfx, u8::is_ascii_lowercase)?;
+    if val.is_empty() {
+        return Err(Error::MissingValue);
+    }
+
+    let offset = pfx.len() + 1;
+    // Crockford decoding is case insensitive, but TypeIDs must be lower case
+    if let Some(index) = val.bytes().position(|c| c.is_ascii_uppercase()) {
+        return Err(Error::InvalidValueChar {
+            index: offset + index,
+            char: val.as_bytes()[index] as char,
+        });
+    }
+    let uuid = Crockford.decode(val).map_err(|e| e.offset(offset))?;
+    Ok((pfx, uuid))
+}
+
+fn check_prefix(prefix: &str, valid: fn(&u8) -> bool) -> Result<()> {
+    if let Some(valid_until) = prefix.bytes().position(|c| !valid(&c)) {
+        return Err(Error::InvalidPrefix { valid_until });
+    }
+    if prefix.len() > MAX_PREFIX_LEN {
+        return Err(Error::PrefixByteLength);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod typeid_tests {
+    use super::*;
+    use crate::{Oid, OidPrefix, OidStr};
+
+    #[derive(Debug, PartialEq)]
+    struct Prefix;
+    impl OidPrefix for Prefix {}
+
+    /// `valid.yml` of the TypeID specification (v0.3.0) as
+    /// `(typeid, prefix, uuid)`
+    const VALID: &[(&str, &str, &str)] = &[
+        (
+            "00000000000000000000000000",
+            "",
+            "00000000-0000-0000-0000-000000000000",
+        ),
+        (
+            "00000000000000000000000001",
+            "",
+            "00000000-0000-0000-0000-000000000001",
+        ),
+        (
+            "0000000000000000000000000a",
+            "",
+            "00000000-0000-0000-0000-00000000000a",
+        ),
+        (
+            "0000000000000000000000000g",
+            "",
+            "00000000-0000-0000-0000-000000000010",
+        ),
+        (
+            "00000000000000000000000010",
+            "",
+            "00000000-0000-0000-0000-000000000020",
+        ),
+        (
+            "7zzzzzzzzzzzzzzzzzzzzzzzzz",
+            "",
+            "ffffffff-ffff-ffff-ffff-ffffffffffff",
+        ),
+        (
+            "prefix_0123456789abcdefghjkmnpqrs",
+            "prefix",
+            "0110c853-1d09-52d8-d73e-1194e95b5f19",
+        ),
+        (
+            "prefix_01h455vb4pex5vsknk084sn02q",
+            "prefix",
+            "01890a5d-ac96-774b-bcce-b302099a8057",
+        ),
+        (
+            "pre_fix_00000000000000000000000000",
+            "pre_fix",
+            "00000000-0000-0000-0000-000000000000",
+        ),
+    ];
+
+    /// `invalid.yml` of the TypeID specification (v0.3.0)
+    const INVALID: &[&str] = &[
+        "PREFIX_00000000000000000000000000",
+        "12345_00000000000000000000000000",
+        "pre.fix_00000000000000000000000000",
+        "préfix_00000000000000000000000000",
+        "  prefix_00000000000000000000000000",
+        "abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyzabcdefghijkl_00000000000000000000000000",
+        "_00000000000000000000000000",
+        "_",
+        "prefix_1234567890123456789012345",
+        "prefix_123456789012345678901234567",
+        "prefix_1234567890123456789012345 ",
+        "prefix_0123456789ABCDEFGHJKMNPQRS",
+        "prefix_123456789-123456789-123456",
+        "prefix_ooooooiiiiiiuuuuuuulllllll",
+        "prefix_i23456789ol23456789oi23456",
+        "prefix_123456789-0123456789-0123456",
+        "prefix_8zzzzzzzzzzzzzzzzzzzzzzzzz",
+        "_prefix_00000000000000000000000000",
+        "prefix__00000000000000000000000000",
+    ];
+
+    #[test]
+    fn spec_valid() {
+        for &(typeid, prefix, uuid) in VALID {
+            let uuid: Uuid = uuid.parse().unwrap();
+            match prefix {
+                "prefix" => {
+                    let oid = Oid::<Prefix>::from_typeid(typeid).unwrap();
+                    assert_eq!(oid.uuid(), &uuid);
+                    assert_eq!(oid.to_typeid().unwrap(), typeid);
+
+                    let oid = OidStr::from_typeid(typeid).unwrap();
+                    assert_eq!(oid.prefix().as_str(), prefix);
+                    assert_eq!(oid.uuid(), &uuid);
+                    assert_eq!(oid.to_typeid().unwrap(), typeid);
+                }
+                // Not representable as a TOID, but the suffix must still decode
+                "" => {
+                    assert_eq!(OidStr::from_typeid(typeid), Err(Error::MissingPrefix));
+                    let with_prefix = format!("prefix_{typeid}");
+                    let oid = OidStr::from_typeid(&with_prefix).unwrap();
+                    assert_eq!(oid.uuid(), &uuid);
+                    assert_eq!(oid.to_typeid().unwrap(), with_prefix);
+                }
+                _ => assert_eq!(
+                    OidStr::from_typeid(typeid),
+                    Err(Error::InvalidPrefix { valid_until: 3 })
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn spec_invalid() {
+        for typeid in INVALID {
+            assert!(
+                OidStr::from_typeid(typeid).is_err(),
+                "{typeid} should be invalid"
+            );
+            assert!(
+                Oid::<Prefix>::from_typeid(typeid).is_err(),
+                "{typeid} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn toid_roundtrip() {
+        let oid: Oid<Prefix> = "Prefix-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        let typeid = oid.to_typeid().unwrap();
+        assert_eq!(typeid, "prefix_067q1t0e95fhzr006agjgjxrc3");
+        assert_eq!(Oid::<Prefix>::from_typeid(&typeid).unwrap(), oid);
+    }
+
+    #[test]
+    fn prefix_not_representable() {
+        let oid: OidStr = "Tst1-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+        assert_eq!(
+            oid.to_typeid(),
+            Err(Error::InvalidPrefix { valid_until: 3 })
+        );
+    }
+
+    #[test]
+    fn error_offsets() {
+        assert_eq!(
+            OidStr::from_typeid("prefix_0123456789ABCDEFGHJKMNPQRS"),
+            Err(Error::InvalidValueChar {
+                index: 17,
+                char: 'A'
+            })
+        );
+        assert_eq!(
+            OidStr::from_typeid("prefix_i23456789ol23456789oi23456"),
+            Err(Error::InvalidValueChar {
+                index: 7,
+                char: 'i'
+            })
+        );
+        assert_eq!(
+            Oid::<Prefix>::from_typeid("other_01h455vb4pex5vsknk084sn02q"),
+            Err(Error::UnexpectedPrefix {
+                expected: "Prefix",
+                found: "other".parse().unwrap()
+            })
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! [`utoipa`](::utoipa) support for TOIDs
+//!
+//! Both [`Oid`] and [`OidStr`] are described as strings with a `pattern`
+//! matching the rendered TOID, an example value and a description. For an
+//! `Oid<P>` these are built from `P` (see the `schemars` integration for the
+//! details of the pattern) and each prefix type is its own named schema.
+//!
+//! `Oid<P>` additionally implements [`IntoParams`] as a single required
+//! parameter named `id` (a path parameter unless specified otherwise), so it
+//! can be used directly as e.g. `Path<Oid<User>>` in documented routes.
+
+use std::borrow::Cow;
+
+use ::utoipa::{
+    openapi::{
+        path::{Parameter, ParameterBuilder, ParameterIn},
+        schema::{ObjectBuilder, Schema, Type},
+        RefOr, Required,
+    },
+    IntoParams, PartialSchema, ToSchema,
+};
+use uuid::Uuid;
+
+use crate::{
+    schema::{oid_pattern, oidstr_pattern},
+    Oid, OidPrefix, OidStr,
+};
+
+/// The UUID of the example values
+const EXAMPLE_UUID: Uuid = Uuid::from_u128(0x063dc3a0_3925_7c7f_8000_ca84a12ee183);
+
+impl<P: OidPrefix> PartialSchema for Oid<P> {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .pattern(Some(oid_pattern::<P>()))
+            .description(Some(format!(
+                "A typed object ID with the prefix `{}`",
+                P::prefix()
+            )))
+            .examples([Oid::<P>::with_uuid(EXAMPLE_UUID).to_string()])
+            .into()
+    }
+}
+
+impl<P: OidPrefix> ToSchema for Oid<P> {
+    fn name() -> Cow<'static, str> { format!("Oid_{}", P::prefix()).into() }
+}
+
+impl<P: OidPrefix> IntoParams for Oid<P> {
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![ParameterBuilder::new()
+            .name("id")
+            .parameter_in(parameter_in_provider().unwrap_or(ParameterIn::Path))
+            .required(Required::True)
+            .description(Some(format!("The `{}` ID", P::prefix())))
+            .schema(Some(Self::schema()))
+            .build()]
+    }
+}
+
+impl PartialSchema for OidStr {
+    fn schema() -> RefOr<Schema> {
+        let example = OidStr::with_uuid("Tst", EXAMPLE_UUID).expect("valid prefix");
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .pattern(Some(oidstr_pattern()))
+            .description(Some("A typed object ID with any prefix"))
+            .examples([example.to_string()])
+            .into()
+    }
+}
+
+impl ToSchema for OidStr {
+    fn name() -> Cow<'static, str> { "OidStr".into() }
+}
+
+#[cfg(test)]
+mod utoipa_tests {
+    use serde_json::{json, to_value};
+
+    use super::*;
+
+    struct Tst;
+    impl OidPrefix for Tst {}
+
+    #[test]
+    fn oid_schema() {
+        assert_eq!(<Oid<Tst> as ToSchema>::name(), "Oid_Tst");
+        assert_eq!(
+            to_value(Oid::<Tst>::schema()).unwrap(),
+            json!({
+                "type": "string",
+                "pattern": "^Tst-[0-9A-V]{26}$",
+                "description": "A typed object ID with the prefix `Tst`",
+                "examples": ["Tst-0OUS781P4LU7V000PA2A2BN1GC"],
+            })
+        );
+    }
+
+    #[test]
+    fn oid_params() {
+        let params = Oid::<Tst>::into_params(|| None);
+        assert_eq!(params.len(), 1);
+        let param = to_value(&params[0]).unwrap();
+        assert_eq!(param["name"], "id");
+        assert_eq!(param["in"], "path");
+        assert_eq!(param["required"], true);
+        assert_eq!(param["schema"]["pattern"], "^Tst-[0-9A-V]{26}$");
+
+        let params = Oid::<Tst>::into_params(|| Some(ParameterIn::Query));
+        assert_eq!(to_value(&params[0]).unwrap()["in"], "query");
+    }
+
+    #[test]
+    fn oidstr_schema() {
+        assert_eq!(<OidStr as ToSchema>::name(), "OidStr");
+        assert_eq!(
+            to_value(OidStr::schema()).unwrap(),
+            json!({
+                "type": "string",
+                "pattern": "^[0-9A-Za-z]+-[0-9A-V]{26}$",
+                "description": "A typed object ID with any prefix",
+                "examples": ["Tst-0OUS781P4LU7V000PA2A2BN1GC"],
+            })
+        );
+    }
+}
@@ -1,12 +1,306 @@
+use core::result::Result as StdResult;
+
 use data_encoding::BASE32HEX_NOPAD;
 use uuid::Uuid;
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    format::Case,
+};
+
+/// The length of a base32hex (no pad) encoded UUID, i.e. the value portion of
+/// an OID
+pub const VALUE_LEN: usize = 26;
+
+/// Encodes a UUID as base32hex (no pad) into `buf` without allocating
+pub(crate) fn uuid_to_str_b32h<'a>(
+    uuid: &Uuid,
+    case: Case,
+    buf: &'a mut [u8; VALUE_LEN],
+) -> &'a str {
+    BASE32HEX_NOPAD.encode_mut(uuid.as_bytes(), buf);
+    case.apply(buf);
+    // SAFETY: The base32hex alphabet is a subset of 7-bit ASCII which itself is a
+    // subset of UTF-8
+    unsafe { core::str::from_utf8_unchecked(buf) }
+}
+
+/// Marks a byte which is not part of the base32hex alphabet in [`B32H_DECODE`]
+const INVALID: u8 = 0xFF;
+
+/// Maps an ASCII byte to its 5-bit base32hex value, or [`INVALID`]. Both upper
+/// and lower case letters are accepted.
+const B32H_DECODE: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+    while i < 32 {
+        if i < 10 {
+            table[(b'0' + i) as usize] = i;
+        } else {
+            table[(b'A' + i - 10) as usize] = i;
+            table[(b'a' + i - 10) as usize] = i;
+        }
+        i += 1;
+    }
+    table
+};
+
+/// Decodes a base32hex (no pad) encoded UUID into its bytes without allocating
+///
+/// On failure returns the index of the first invalid character. The two
+/// trailing bits of the final character must be zero, otherwise the encoding is
+/// not canonical and the final character is considered invalid.
+pub(crate) const fn decode_b32h(s: &[u8; VALUE_LEN]) -> StdResult<[u8; 16], usize> {
+    let mut vals = [0u8; VALUE_LEN];
+    let mut acc = 0;
+    let mut i = 0;
+    while i < VALUE_LEN {
+        vals[i] = B32H_DECODE[s[i] as usize];
+        acc |= vals[i];
+        i += 1;
+    }
+
+    // Only invalid bytes have any bits set above the low five, so the bad input can
+    // be located after the fact rather than checking each byte individually
+    if acc > 0x1F {
+        let mut i = 0;
+        while vals[i] != INVALID {
+            i += 1;
+        }
+        return Err(i);
+    }
+    if vals[VALUE_LEN - 1] & 0b11 != 0 {
+        return Err(VALUE_LEN - 1);
+    }
+
+    // Every 8 characters (40 bits) decode to 5 bytes
+    let mut bytes = [0; 16];
+    let mut group = 0;
+    while group < 3 {
+        let c = group * 8;
+        let mut bits = 0u64;
+        let mut j = 0;
+        while j < 8 {
+            bits = (bits << 5) | vals[c + j] as u64;
+            j += 1;
+        }
+        let b = group * 5;
+        bytes[b] = (bits >> 32) as u8;
+        bytes[b + 1] = (bits >> 24) as u8;
+        bytes[b + 2] = (bits >> 16) as u8;
+        bytes[b + 3] = (bits >> 8) as u8;
+        bytes[b + 4] = bits as u8;
+        group += 1;
+    }
+    // The remaining 2 characters hold the final byte plus the two padding bits
+    bytes[15] = (vals[24] << 3) | (vals[25] >> 2);
+
+    Ok(bytes)
+}
 
 /// Converts a Base32hex encoded UUID string into a UUID
+///
+/// Any indexes in returned errors are relative to the start of `s`
 pub(crate) fn uuid_from_str_b32h(s: &str) -> Result<Uuid> {
     if s.is_empty() {
         return Err(Error::MissingValue);
     }
-    Ok(Uuid::from_slice(&BASE32HEX_NOPAD.decode(s.as_bytes())?)?)
+    let value: &[u8; VALUE_LEN] =
+        s.as_bytes()
+            .try_into()
+            .map_err(|_| Error::WrongValueLength {
+                expected: VALUE_LEN,
+                found: s.len(),
+            })?;
+    decode_b32h(value)
+        .map(Uuid::from_bytes)
+        .map_err(|index| Error::InvalidValueChar {
+            index,
+            char: s
+                .get(index..)
+                .and_then(|s| s.chars().next())
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        })
+}
+
+/// The namespace of the default per-prefix UUIDv5 namespaces, which is itself
+/// the UUIDv5 of `https://crates.io/crates/typed-oid` in the URL namespace
+#[cfg(feature = "uuid_v5")]
+const PREFIX_NAMESPACE: Uuid = Uuid::from_u128(0xb7de34af_f1ae_5ec2_9bc6_24adde87a48f);
+
+/// The default namespace of name-based UUIDv5s for a prefix
+#[cfg(feature = "uuid_v5")]
+pub(crate) fn prefix_namespace(prefix: &str) -> Uuid {
+    Uuid::new_v5(&PREFIX_NAMESPACE, prefix.as_bytes())
+}
+
+#[cfg(test)]
+#[cfg(feature = "uuid_v5")]
+mod prefix_namespace_tests {
+    use super::*;
+
+    #[test]
+    fn namespace() {
+        assert_eq!(
+            PREFIX_NAMESPACE,
+            Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://crates.io/crates/typed-oid")
+        );
+        assert_eq!(
+            prefix_namespace("Tst").to_string(),
+            "e8b54df6-2005-5397-880f-0386fcb9a1c7"
+        );
+        assert_ne!(prefix_namespace("Tst"), prefix_namespace("Frm"));
+    }
+}
+
+#[cfg(test)]
+mod uuid_to_str_b32h_tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let uuid: Uuid = "063dc3a0-3925-7c7f-8000-ca84a12ee183".parse().unwrap();
+        let mut buf = [0; VALUE_LEN];
+        assert_eq!(
+            uuid_to_str_b32h(&uuid, Case::Upper, &mut buf),
+            "0OUS781P4LU7V000PA2A2BN1GC"
+        );
+        assert_eq!(
+            uuid_to_str_b32h(&uuid, Case::Upper, &mut buf),
+            BASE32HEX_NOPAD.encode(uuid.as_bytes())
+        );
+    }
+
+    #[test]
+    fn encode_lower() {
+        let uuid: Uuid = "063dc3a0-3925-7c7f-8000-ca84a12ee183".parse().unwrap();
+        let mut buf = [0; VALUE_LEN];
+        assert_eq!(
+            uuid_to_str_b32h(&uuid, Case::Lower, &mut buf),
+            "0ous781p4lu7v000pa2a2bn1gc"
+        );
+    }
+}
+
+#[cfg(test)]
+mod uuid_from_str_b32h_tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            uuid_from_str_b32h("0OUS781P4LU7V000PA2A2BN1GC").unwrap(),
+            "063dc3a0-3925-7c7f-8000-ca84a12ee183"
+                .parse::<Uuid>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        assert_eq!(
+            uuid_from_str_b32h("0OUS781P4LU7V000PA2A2BN1G"),
+            Err(Error::WrongValueLength {
+                expected: 26,
+                found: 25
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_char() {
+        assert_eq!(
+            uuid_from_str_b32h("0OUS781P4LU7&000PA2A2BN1GC"),
+            Err(Error::InvalidValueChar {
+                index: 12,
+                char: '&'
+            })
+        );
+    }
+
+    #[test]
+    fn non_canonical_trailing_bits() {
+        assert_eq!(
+            uuid_from_str_b32h("0OUS781P4LU7V000PA2A2BN1GD"),
+            Err(Error::InvalidValueChar {
+                index: 25,
+                char: 'D'
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod decode_b32h_tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const CONST_DECODED: StdResult<[u8; 16], usize> = decode_b32h(b"0OUS781P4LU7V000PA2A2BN1GC");
+
+    #[test]
+    fn const_eval() {
+        assert_eq!(
+            CONST_DECODED.map(Uuid::from_bytes),
+            Ok("063dc3a0-3925-7c7f-8000-ca84a12ee183".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn min_max() {
+        assert_eq!(decode_b32h(b"00000000000000000000000000"), Ok([0; 16]));
+        assert_eq!(decode_b32h(b"VVVVVVVVVVVVVVVVVVVVVVVVVS"), Ok([0xFF; 16]));
+        assert_eq!(decode_b32h(b"VVVVVVVVVVVVVVVVVVVVVVVVVV"), Err(25));
+    }
+
+    #[test]
+    fn first_invalid_index() {
+        assert_eq!(decode_b32h(b"0OUS781P4LU7V000PA2A2BN1G\xFF"), Err(25));
+        assert_eq!(decode_b32h(b"W0US781P4LU7V00&PA2A2BN1GC"), Err(0));
+        assert_eq!(decode_b32h(b"0OUS781P4LU7V00&PA2A2BN1G="), Err(15));
+    }
+
+    #[test]
+    fn lowercase() {
+        assert_eq!(
+            decode_b32h(b"0ous781p4lu7v000pa2a2bn1gc"),
+            decode_b32h(b"0OUS781P4LU7V000PA2A2BN1GC")
+        );
+        assert_eq!(
+            decode_b32h(b"0oUs781P4lu7V000pa2A2bn1Gc"),
+            decode_b32h(b"0OUS781P4LU7V000PA2A2BN1GC")
+        );
+        assert_eq!(decode_b32h(b"0OUS781P4LU7V000PA2A2BN1Gw"), Err(25));
+    }
+
+    fn differential(s: &[u8; VALUE_LEN]) -> std::result::Result<(), TestCaseError> {
+        // data_encoding's base32hex only accepts upper case
+        let mut upper = *s;
+        upper.make_ascii_uppercase();
+        let s = &upper;
+        let mut expected = [0; 16];
+        match BASE32HEX_NOPAD.decode_mut(s, &mut expected) {
+            Ok(_) => prop_assert_eq!(decode_b32h(s), Ok(expected)),
+            Err(e) => prop_assert_eq!(decode_b32h(s), Err(e.error.position)),
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn matches_data_encoding_valid_alphabet(s in "[0-9A-V]{26}") {
+            differential(s.as_bytes().try_into().unwrap())?;
+        }
+
+        #[test]
+        fn matches_data_encoding_any_bytes(s in any::<[u8; VALUE_LEN]>()) {
+            differential(&s)?;
+        }
+
+        #[test]
+        fn roundtrip(bytes in any::<[u8; 16]>()) {
+            let encoded = BASE32HEX_NOPAD.encode(&bytes);
+            prop_assert_eq!(decode_b32h(encoded.as_bytes().try_into().unwrap()), Ok(bytes));
+        }
+    }
 }
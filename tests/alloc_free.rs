@@ -0,0 +1,118 @@
+//! Ensures encoding and formatting OIDs does not allocate
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Write,
+};
+
+use typed_oid::{Oid, OidPrefix, OidStr, MAX_VALUE_LEN};
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+struct Tst;
+impl OidPrefix for Tst {}
+
+#[test]
+fn oid() {
+    let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+    let mut out = String::with_capacity(64);
+
+    assert_eq!(
+        allocations(|| {
+            let mut buf = [0; MAX_VALUE_LEN];
+            oid.encode_value(&mut buf);
+        }),
+        0
+    );
+    assert_eq!(
+        allocations(|| {
+            let _buf = oid.to_buf();
+        }),
+        0
+    );
+    assert_eq!(allocations(|| oid.write_to(&mut out).unwrap()), 0);
+    out.clear();
+    assert_eq!(allocations(|| write!(out, "{oid}").unwrap()), 0);
+}
+
+#[test]
+fn oidstr() {
+    let oid: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+    let mut out = String::with_capacity(64);
+
+    assert_eq!(
+        allocations(|| {
+            let mut buf = [0; MAX_VALUE_LEN];
+            oid.encode_value(&mut buf);
+        }),
+        0
+    );
+    assert_eq!(
+        allocations(|| {
+            let _buf = oid.to_buf();
+        }),
+        0
+    );
+    assert_eq!(allocations(|| oid.write_to(&mut out).unwrap()), 0);
+    out.clear();
+    assert_eq!(allocations(|| write!(out, "{oid}").unwrap()), 0);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize() {
+    let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+    let oidstr: OidStr = "TST-0OQPKOAADLRUJ000J7U2UGNS2G".parse().unwrap();
+    let mut out = Vec::with_capacity(64);
+
+    assert_eq!(
+        allocations(|| serde_json::to_writer(&mut out, &oid).unwrap()),
+        0
+    );
+    out.clear();
+    assert_eq!(
+        allocations(|| serde_json::to_writer(&mut out, &oidstr).unwrap()),
+        0
+    );
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn deserialize() {
+    let oid: Oid<Tst> = "Tst-0OUS781P4LU7V000PA2A2BN1GC".parse().unwrap();
+
+    assert_eq!(
+        allocations(|| {
+            let parsed: Oid<Tst> =
+                serde_json::from_str(r#""Tst-0OUS781P4LU7V000PA2A2BN1GC""#).unwrap();
+            assert_eq!(parsed.uuid(), oid.uuid());
+        }),
+        0
+    );
+    // Strings containing escapes can't be borrowed from the input
+    let parsed: Oid<Tst> = serde_json::from_str(r#""Tst-0OUS781P4LU7V000PA2A2BN1G\u0043""#).unwrap();
+    assert_eq!(parsed.uuid(), oid.uuid());
+}
@@ -0,0 +1,358 @@
+//! Derive macros for [`typed-oid`](https://docs.rs/typed-oid)
+//!
+//! This crate is not intended to be used directly, instead enable the `derive`
+//! feature of `typed-oid` and use `typed_oid::OidPrefix`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    bracketed, parse_macro_input, punctuated::Punctuated, DeriveInput, Error, Ident, LitChar,
+    LitStr, Path, Result, Token,
+};
+
+/// Derives `typed_oid::OidPrefix` for a type, validating the prefix at compile
+/// time.
+///
+/// The prefix defaults to the name of the type, and may be overridden with
+/// `#[oid(prefix = "...")]`. Additional prefixes that should be accepted when
+/// parsing can be declared with `#[oid(aliases = ["...", ...])]`.
+///
+/// Prefixes (and aliases) may only contain 7-bit ASCII characters of `0-9`,
+/// `A-Z`, or `a-z`, anything else is a compile error. Without the `alloc`
+/// feature of `typed-oid` they are also limited to 8 bytes, as that is all a
+/// `Prefix` can hold, and longer ones are a compile error too.
+///
+/// Additionally the following attributes map to the respective `OidPrefix`
+/// methods:
+///
+/// - `#[oid(ignore_prefix_case)]` accepts the prefix (and aliases) in any ASCII
+///   case when parsing
+/// - `#[oid(value_case = "lower")]` (or `"upper"`) sets the case used to render
+///   the value
+/// - `#[oid(encoding = path::to::Encoding)]` sets the `OidEncoding` of the
+///   value, e.g. `typed_oid::encoding::Crockford`
+/// - `#[oid(separator = '_')]` sets the separator between the prefix and value
+/// - `#[oid(alt_separator = '-')]` sets an additional separator accepted when
+///   parsing
+///
+/// Separators must not be ASCII alphanumeric, anything else is a compile error.
+///
+/// # Examples
+///
+/// ```rust
+/// use typed_oid::{Oid, OidPrefix};
+///
+/// #[derive(OidPrefix)]
+/// #[oid(prefix = "usr", aliases = ["user"])]
+/// struct User;
+///
+/// let oid: Oid<User> = "user-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+/// assert_eq!(oid.to_string(), "usr-4GKFGPRVND4QT3PDR90PDKF66O");
+///
+/// #[derive(OidPrefix)]
+/// #[oid(prefix = "ord", aliases = ["order"], ignore_prefix_case, value_case = "lower")]
+/// struct Order;
+///
+/// let oid: Oid<Order> = "ORDER-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+/// assert_eq!(oid.to_string(), "ord-4gkfgprvnd4qt3pdr90pdkf66o");
+///
+/// #[derive(OidPrefix)]
+/// #[oid(prefix = "evt", encoding = typed_oid::encoding::Crockford)]
+/// struct Event;
+///
+/// let oid: Oid<Event> = "evt-01H455VB4PEX5VSKNK084SN02Q".parse().unwrap();
+/// assert_eq!(oid.to_string(), "evt-01H455VB4PEX5VSKNK084SN02Q");
+///
+/// #[derive(OidPrefix)]
+/// #[oid(prefix = "acct", separator = '_', alt_separator = '-')]
+/// struct Account;
+///
+/// let oid: Oid<Account> = "acct-4GKFGPRVND4QT3PDR90PDKF66O".parse().unwrap();
+/// assert_eq!(oid.to_string(), "acct_4GKFGPRVND4QT3PDR90PDKF66O");
+/// ```
+///
+/// An invalid prefix fails to compile:
+///
+/// ```compile_fail
+/// use typed_oid::OidPrefix;
+///
+/// #[derive(OidPrefix)]
+/// #[oid(prefix = "us_r")]
+/// struct User;
+/// ```
+///
+/// As does an alphanumeric separator:
+///
+/// ```compile_fail
+/// use typed_oid::OidPrefix;
+///
+/// #[derive(OidPrefix)]
+/// #[oid(separator = 'x')]
+/// struct User;
+/// ```
+///
+/// Or a type name which is not a valid prefix when no prefix is given:
+///
+/// ```compile_fail
+/// use typed_oid::OidPrefix;
+///
+/// #[derive(OidPrefix)]
+/// struct User_Account;
+/// ```
+#[proc_macro_derive(OidPrefix, attributes(oid))]
+pub fn derive_oid_prefix(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let attrs = OidAttrs::from_input(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let prefix = match attrs.prefix {
+        Some(prefix) => prefix,
+        None => {
+            let prefix = LitStr::new(&name.to_string(), name.span());
+            validate_prefix(&prefix).map_err(|e| {
+                Error::new(
+                    name.span(),
+                    format!("{e}; use `#[oid(prefix = \"...\")]` to provide a valid prefix"),
+                )
+            })?;
+            prefix
+        }
+    };
+
+    let aliases = &attrs.aliases;
+    let max_len = aliases
+        .iter()
+        .chain([&prefix])
+        .map(|p| p.value().len())
+        .max()
+        .unwrap_or_default();
+    let (aliases, str_partial_eq) = if aliases.is_empty() {
+        (quote!(), quote!())
+    } else if attrs.ignore_prefix_case {
+        (
+            quote!(fn aliases() -> &'static [&'static str] { &[#(#aliases),*] }),
+            quote! {
+                fn str_partial_eq(s: &str) -> bool {
+                    [#prefix #(, #aliases)*].iter().any(|p| p.eq_ignore_ascii_case(s))
+                }
+            },
+        )
+    } else {
+        (
+            quote!(fn aliases() -> &'static [&'static str] { &[#(#aliases),*] }),
+            quote!(),
+        )
+    };
+
+    let ignore_prefix_case = if attrs.ignore_prefix_case {
+        quote!(fn ignore_prefix_case() -> bool { true })
+    } else {
+        quote!()
+    };
+
+    let value_case = match attrs.value_case {
+        Some(case) => quote!(fn value_case() -> ::typed_oid::Case { ::typed_oid::Case::#case }),
+        None => quote!(),
+    };
+
+    let encoding = match attrs.encoding {
+        Some(encoding) => quote!(fn encoding() -> &'static dyn ::typed_oid::OidEncoding { &#encoding }),
+        None => quote!(),
+    };
+
+    let separator = match attrs.separator {
+        Some(separator) => quote!(fn separator() -> char { #separator }),
+        None => quote!(),
+    };
+
+    let alt_separator = match attrs.alt_separator {
+        Some(separator) => quote!(fn alt_separator() -> Option<char> { Some(#separator) }),
+        None => quote!(),
+    };
+
+    Ok(quote! {
+        const _: () = assert!(
+            #max_len <= ::typed_oid::__private::MAX_PREFIX_LEN,
+            "OID prefixes longer than 8 bytes require the `alloc` feature of typed-oid"
+        );
+
+        impl #impl_generics ::typed_oid::OidPrefix for #name #ty_generics #where_clause {
+            fn prefix() -> &'static str { #prefix }
+
+            #aliases
+
+            #str_partial_eq
+
+            #ignore_prefix_case
+
+            #value_case
+
+            #encoding
+
+            #separator
+
+            #alt_separator
+        }
+    })
+}
+
+#[derive(Default)]
+struct OidAttrs {
+    prefix: Option<LitStr>,
+    aliases: Vec<LitStr>,
+    ignore_prefix_case: bool,
+    value_case: Option<Ident>,
+    encoding: Option<Path>,
+    separator: Option<LitChar>,
+    alt_separator: Option<LitChar>,
+}
+
+impl OidAttrs {
+    fn from_input(input: &DeriveInput) -> Result<Self> {
+        let mut attrs = Self::default();
+
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("oid")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("prefix") {
+                    if attrs.prefix.is_some() {
+                        return Err(meta.error("duplicate `prefix` attribute"));
+                    }
+                    let prefix: LitStr = meta.value()?.parse()?;
+                    validate_prefix(&prefix)?;
+                    attrs.prefix = Some(prefix);
+                    Ok(())
+                } else if meta.path.is_ident("aliases") {
+                    let value = meta.value()?;
+                    let content;
+                    bracketed!(content in value);
+                    for alias in Punctuated::<LitStr, Token![,]>::parse_terminated(&content)? {
+                        validate_prefix(&alias)?;
+                        attrs.aliases.push(alias);
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("ignore_prefix_case") {
+                    attrs.ignore_prefix_case = true;
+                    Ok(())
+                } else if meta.path.is_ident("value_case") {
+                    let case: LitStr = meta.value()?.parse()?;
+                    let variant = match case.value().as_str() {
+                        "upper" => "Upper",
+                        "lower" => "Lower",
+                        _ => {
+                            return Err(Error::new(
+                                case.span(),
+                                "expected `value_case = \"upper\"` or `value_case = \"lower\"`",
+                            ))
+                        }
+                    };
+                    attrs.value_case = Some(Ident::new(variant, case.span()));
+                    Ok(())
+                } else if meta.path.is_ident("encoding") {
+                    attrs.encoding = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("separator") {
+                    let separator: LitChar = meta.value()?.parse()?;
+                    validate_separator(&separator)?;
+                    attrs.separator = Some(separator);
+                    Ok(())
+                } else if meta.path.is_ident("alt_separator") {
+                    let separator: LitChar = meta.value()?.parse()?;
+                    validate_separator(&separator)?;
+                    attrs.alt_separator = Some(separator);
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unknown `oid` attribute, expected one of `prefix`, `aliases`, \
+                         `ignore_prefix_case`, `value_case`, `encoding`, `separator`, or \
+                         `alt_separator`",
+                    ))
+                }
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
+
+// NOTE: Must be kept in sync with `typed_oid::prefix::valid_prefix_char`, which
+// is checked by `validate_prefix_tests::matches_runtime`
+#[inline]
+fn valid_prefix_char(c: u8) -> bool {
+    (c > b'/' && c < b':') || (c > b'`' && c < b'{') || (c > b'@' && c < b'[')
+}
+
+fn validate_prefix(prefix: &LitStr) -> Result<()> {
+    let value = prefix.value();
+    if value.is_empty() {
+        return Err(Error::new(prefix.span(), "OID prefix must not be empty"));
+    }
+    if let Some((i, c)) = value
+        .char_indices()
+        .find(|&(_, c)| !c.is_ascii() || !valid_prefix_char(c as u8))
+    {
+        return Err(Error::new(
+            prefix.span(),
+            format!(
+                "invalid character {c:?} at index {i} in OID prefix {value:?}; prefix characters \
+                 may only be 7-bit ASCII values of 0-9,a-z,A-Z"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_separator(separator: &LitChar) -> Result<()> {
+    if separator.value().is_ascii_alphanumeric() {
+        return Err(Error::new(
+            separator.span(),
+            "OID separator must not be ASCII alphanumeric as it would be ambiguous with the \
+             prefix or value",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod validate_prefix_tests {
+    use proc_macro2::Span;
+
+    use super::*;
+
+    fn lit(s: &str) -> LitStr { LitStr::new(s, Span::call_site()) }
+
+    #[test]
+    fn valid() {
+        assert!(validate_prefix(&lit("usr")).is_ok());
+        assert!(validate_prefix(&lit("TestingTesting")).is_ok());
+        assert!(validate_prefix(&lit("a0Z9")).is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(validate_prefix(&lit("")).is_err());
+        assert!(validate_prefix(&lit("us_r")).is_err());
+        assert!(validate_prefix(&lit("us-r")).is_err());
+        assert!(validate_prefix(&lit("usé")).is_err());
+    }
+
+    #[test]
+    fn matches_runtime() {
+        for c in (0..=u8::MAX).map(char::from) {
+            for prefix in [c.to_string(), format!("Tst{c}Tst")] {
+                assert_eq!(
+                    validate_prefix(&lit(&prefix)).is_ok(),
+                    prefix.parse::<typed_oid::Prefix>().is_ok(),
+                    "{prefix:?}"
+                );
+            }
+        }
+    }
+}